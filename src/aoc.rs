@@ -0,0 +1,382 @@
+//! Session cookie handling and the puzzle unlock schedule, shared by the commands that talk to adventofcode.com.
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const SERVER: &str = "https://adventofcode.com";
+pub const FIRST_YEAR: i32 = 2015;
+pub const SESSION_FILE: &str = ".adventofcode.session";
+
+/// Session cookies expire after about a month.
+pub const SESSION_LIFETIME_DAYS: u64 = 30;
+pub const SESSION_WARNING_DAYS: u64 = 25;
+
+/// The server to talk to. Can be overridden with the `AOC_SERVER` environment variable.
+pub fn server() -> String {
+    env::var("AOC_SERVER")
+        .map(|s| s.trim_end_matches('/').to_owned())
+        .unwrap_or_else(|_| SERVER.to_owned())
+}
+
+/// Location of the session cookie file that aoc-cli reads, i.e. `~/.adventofcode.session`.
+pub fn session_path() -> eyre::Result<PathBuf> {
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .ok_or(eyre::eyre!("Cannot determine home directory"))?;
+    Ok(PathBuf::from(home).join(SESSION_FILE))
+}
+
+pub fn read_session() -> eyre::Result<String> {
+    let path = session_path()?;
+    let cookie = fs::read_to_string(&path)
+        .map_err(|e| eyre::eyre!("Cannot read session cookie from {:?}: {}", path, e))?;
+    Ok(cookie.trim().to_owned())
+}
+
+/// Writes the session cookie so that only the current user can read it.
+pub fn write_session(cookie: &str) -> eyre::Result<PathBuf> {
+    let path = session_path()?;
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to newly created files.
+        if path.exists() {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(cookie.as_bytes())?;
+    Ok(path)
+}
+
+/// Runs curl with `args`, sending the session cookie as a header on stdin so that it never shows
+/// up in the process list.
+pub fn curl_with_session(cookie: &str, args: &[&str]) -> io::Result<Output> {
+    let mut child = Command::new("curl")
+        .args(["--header", "@-"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Cookie: session={}", cookie)?;
+    }
+    child.wait_with_output()
+}
+
+/// Time since the session cookie was last written.
+pub fn session_age() -> eyre::Result<Duration> {
+    let modified = fs::metadata(session_path()?)?.modified()?;
+    Ok(SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default())
+}
+
+pub fn session_age_warning(age: Duration) -> Option<String> {
+    let days = age.as_secs() / 86400;
+    if days >= SESSION_LIFETIME_DAYS {
+        Some(format!(
+            "⚠️  Your session cookie is {} days old and has most likely expired. Refresh it with `cargo session set`.",
+            days
+        ))
+    } else if days >= SESSION_WARNING_DAYS {
+        Some(format!(
+            "⚠️  Your session cookie is {} days old and will expire soon. Refresh it with `cargo session set`.",
+            days
+        ))
+    } else {
+        None
+    }
+}
+
+/// Shows only the first and last four characters of a cookie.
+pub fn mask_session(cookie: &str) -> String {
+    if cookie.len() <= 8 || !cookie.is_ascii() {
+        return "*".repeat(cookie.chars().count());
+    }
+    format!(
+        "{}{}{}",
+        &cookie[..4],
+        "*".repeat(cookie.len() - 8),
+        &cookie[cookie.len() - 4..]
+    )
+}
+
+pub fn now_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Puzzles unlock at midnight US Eastern (UTC-5 in December), i.e. 05:00 UTC.
+pub fn unlock_timestamp(year: i32, day: u8) -> i64 {
+    days_from_civil(year as i64, 12, day as u32) * 86400 + 5 * 3600
+}
+
+/// The most recent event whose first puzzle has unlocked at `now`.
+pub fn event_year_at(now: i64) -> i32 {
+    let (year, _, _) = civil_from_days(now.div_euclid(86400));
+    let year = year as i32;
+    if now >= unlock_timestamp(year, 1) {
+        year
+    } else {
+        year - 1
+    }
+}
+
+/// The event that is running or coming up next at `now`, going by the calendar year in US Eastern
+/// time. This is the event `--wait` waits for.
+pub fn upcoming_event_year_at(now: i64) -> i32 {
+    let (year, _, _) = civil_from_days((now - 5 * 3600).div_euclid(86400));
+    year as i32
+}
+
+/// The last puzzle of an event: 25 days until 2024, 12 days from 2025 on.
+pub fn last_day(year: i32) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+pub fn check_puzzle_exists(year: i32, day: u8) -> eyre::Result<()> {
+    if year < FIRST_YEAR {
+        eyre::bail!(
+            "There is no event in {}. Advent of Code started in {}.",
+            year,
+            FIRST_YEAR
+        );
+    }
+    let last = last_day(year);
+    if !(1..=last).contains(&day) {
+        eyre::bail!(
+            "There is no puzzle for day {} in {}. Days range from 1 to {}.",
+            day,
+            year,
+            last
+        );
+    }
+    Ok(())
+}
+
+/// Source of the current time, so that unlock handling can be tested without waiting.
+pub trait Clock {
+    fn now(&self) -> i64;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        now_timestamp()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+/// Seconds until the puzzle unlocks, or `None` if it is available already.
+pub fn time_until_unlock(clock: &impl Clock, year: i32, day: u8) -> Option<i64> {
+    let remaining = unlock_timestamp(year, day) - clock.now();
+    if remaining > 0 {
+        Some(remaining)
+    } else {
+        None
+    }
+}
+
+/// Sleeps until the puzzle unlocks, calling `on_tick` with the remaining seconds once per second.
+pub fn wait_for_unlock(clock: &impl Clock, year: i32, day: u8, mut on_tick: impl FnMut(i64)) {
+    while let Some(remaining) = time_until_unlock(clock, year, day) {
+        on_tick(remaining);
+        clock.sleep(Duration::from_secs(1));
+    }
+}
+
+/// Fails with a hint to use `--wait` if the puzzle is not available yet, or waits for it with a
+/// countdown if `wait` is set.
+pub fn ensure_unlocked(clock: &impl Clock, year: i32, day: u8, wait: bool) -> eyre::Result<()> {
+    check_puzzle_exists(year, day)?;
+    let remaining = match time_until_unlock(clock, year, day) {
+        Some(remaining) => remaining,
+        None => return Ok(()),
+    };
+
+    if !wait {
+        eyre::bail!(
+            "Day {} of {} unlocks in {}. Append `--wait` to wait for it.",
+            day,
+            year,
+            format_duration(remaining)
+        );
+    }
+
+    wait_for_unlock(clock, year, day, |remaining| {
+        print!(
+            "\r⏳ Day {} of {} unlocks in {} ",
+            day,
+            year,
+            format_duration(remaining)
+        );
+        #[allow(unused_must_use)]
+        {
+            io::stdout().flush();
+        }
+    });
+    println!(
+        "\r🎄 Day {} of {} is unlocked!{}",
+        day,
+        year,
+        " ".repeat(16)
+    );
+    Ok(())
+}
+
+// see: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// see: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+/// Formats a duration in seconds as `h:mm:ss`, prefixed by whole days if needed.
+pub fn format_duration(secs: i64) -> String {
+    let (days, rest) = (secs / 86400, secs % 86400);
+    let hms = format!("{}:{:02}:{:02}", rest / 3600, (rest % 3600) / 60, rest % 60);
+    if days > 0 {
+        format!("{}d {}", days, hms)
+    } else {
+        hms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_session_age_warning() {
+        let days = |d: u64| Duration::from_secs(d * 86400);
+        assert_eq!(session_age_warning(days(3)), None);
+        assert!(session_age_warning(days(26))
+            .unwrap()
+            .contains("will expire soon"));
+        assert!(session_age_warning(days(31))
+            .unwrap()
+            .contains("has most likely expired"));
+    }
+
+    #[test]
+    fn test_mask_session() {
+        assert_eq!(mask_session("0123456789abcdef"), "0123********cdef");
+        assert_eq!(mask_session("short"), "*****");
+    }
+
+    #[test]
+    fn test_unlock_timestamp() {
+        // 2022-12-01T05:00:00Z
+        assert_eq!(unlock_timestamp(2022, 1), 1669870800);
+        assert_eq!(unlock_timestamp(2022, 15), 1669870800 + 14 * 86400);
+    }
+
+    #[test]
+    fn test_event_year_at() {
+        assert_eq!(event_year_at(unlock_timestamp(2022, 1)), 2022);
+        assert_eq!(event_year_at(unlock_timestamp(2022, 1) - 1), 2021);
+        assert_eq!(event_year_at(unlock_timestamp(2022, 25) + 40 * 86400), 2022);
+    }
+
+    #[test]
+    fn test_upcoming_event_year_at() {
+        // the evening before the event starts, US Eastern.
+        assert_eq!(
+            upcoming_event_year_at(unlock_timestamp(2022, 1) - 3600),
+            2022
+        );
+        // "day 32" is New Year's midnight.
+        assert_eq!(upcoming_event_year_at(unlock_timestamp(2022, 32) - 1), 2022);
+        assert_eq!(upcoming_event_year_at(unlock_timestamp(2022, 32)), 2023);
+    }
+
+    #[test]
+    fn test_check_puzzle_exists() {
+        assert!(check_puzzle_exists(2022, 1).is_ok());
+        assert!(check_puzzle_exists(2022, 26).is_err());
+        assert!(check_puzzle_exists(2022, 0).is_err());
+        assert!(check_puzzle_exists(2014, 1).is_err());
+        assert!(check_puzzle_exists(2024, 25).is_ok());
+        assert!(check_puzzle_exists(2025, 12).is_ok());
+        assert_eq!(
+            check_puzzle_exists(2025, 13).unwrap_err().to_string(),
+            "There is no puzzle for day 13 in 2025. Days range from 1 to 12."
+        );
+    }
+
+    struct FakeClock(Cell<i64>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> i64 {
+            self.0.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.0.set(self.0.get() + duration.as_secs() as i64);
+        }
+    }
+
+    #[test]
+    fn test_time_until_unlock() {
+        let clock = FakeClock(Cell::new(unlock_timestamp(2022, 15) - 90));
+        assert_eq!(time_until_unlock(&clock, 2022, 15), Some(90));
+        assert_eq!(time_until_unlock(&clock, 2022, 14), None);
+    }
+
+    #[test]
+    fn test_wait_for_unlock() {
+        let clock = FakeClock(Cell::new(unlock_timestamp(2022, 15) - 3));
+        let mut ticks = vec![];
+        wait_for_unlock(&clock, 2022, 15, |remaining| ticks.push(remaining));
+        assert_eq!(ticks, vec![3, 2, 1]);
+        assert_eq!(clock.now(), unlock_timestamp(2022, 15));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(59), "0:00:59");
+        assert_eq!(format_duration(3 * 3600 + 62), "3:01:02");
+        assert_eq!(format_duration(86400 + 3600), "1d 1:00:00");
+    }
+}
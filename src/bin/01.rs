@@ -1,65 +1,265 @@
-use itertools::Itertools;
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{BufRead, Lines},
+};
 
-pub fn part_one(input: &str) -> color_eyre::Result<u32> {
-    let mut max: Option<u32> = None;
-    let iter = input.split('\n');
-    let mut current: Option<u32> = None;
-
-    for i in iter.into_iter() {
-        if i.is_empty() {
-            // update max
-            if let Some(c) = current {
-                max = if let Some(m) = max {
-                    Some(if c > m { c } else { m })
-                } else {
-                    Some(c)
-                };
-                current = None;
-            }
-        } else {
-            current = if let Some(c) = current {
-                Some(c + i.parse::<u32>().unwrap())
-            } else {
-                Some(i.parse::<u32>().unwrap())
+use advent_of_code::helpers::error::WithLine;
+
+/// The snacks carried by one elf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Elf {
+    /// 1-based position of the elf in the input.
+    index: usize,
+    items: usize,
+    total: u32,
+}
+
+/// Streams the elves of an inventory; elves are separated by blank lines.
+struct Elves<R> {
+    lines: Lines<R>,
+    line_number: usize,
+    next_index: usize,
+}
+
+impl<R: BufRead> Elves<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            line_number: 0,
+            next_index: 1,
+        }
+    }
+
+    fn next_elf(&mut self) -> eyre::Result<Option<Elf>> {
+        let mut elf: Option<Elf> = None;
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                if elf.is_some() {
+                    break;
+                }
+                continue;
             }
+            let calories = line
+                .trim()
+                .parse::<u32>()
+                .with_line(self.line_number, &line)?;
+            let elf = elf.get_or_insert(Elf {
+                index: self.next_index,
+                items: 0,
+                total: 0,
+            });
+            elf.items += 1;
+            elf.total = elf
+                .total
+                .checked_add(calories)
+                .ok_or(eyre::eyre!("Total calories overflow"))
+                .with_line(self.line_number, &line)?;
         }
+        if elf.is_some() {
+            self.next_index += 1;
+        }
+        Ok(elf)
     }
-    match max {
-        Some(s) => Ok(s),
-        None => Err(eyre::eyre!("invalid")),
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = eyre::Result<Elf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_elf().transpose()
     }
 }
 
-pub fn part_two(input: &str) -> color_eyre::Result<u32> {
-    let top_3 = input
-        .split('\n')
-        // translate into [Some(1), Some(2), None, Some(3), Some(4), ...]
-        .map(|s| s.parse::<u32>().ok())
-        // group them by is_some
-        // [ (true, [Some(1), Some(2)]), (false, [None]), (true, [Some(3), Some(4)]), ...]
-        .group_by(|s| s.is_some())
+/// The `k` elves carrying the most calories as `(index, total)`, most first.
+/// Ties go to the elf that comes first.
+fn top_k_elves(reader: impl BufRead, k: usize) -> eyre::Result<Vec<(usize, u32)>> {
+    // a min-heap of the best `k` so far, so the weakest of them is always on top.
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for elf in Elves::new(reader) {
+        let elf = elf?;
+        heap.push(Reverse((elf.total, Reverse(elf.index))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    Ok(heap
+        .into_sorted_vec()
         .into_iter()
-        // filter out None groups
-        // [ (true, [Some(1), Some(2)]), (true, [Some(3), Some(4)]), ...]
-        .filter(|(is_some, _group)| *is_some)
-        // map to take only _.1 while unwrapping and summing up
-        .map(|(_, group)| {
-            group
-                .into_iter()
-                // [ [1, 2], [3, 4], ...]
-                .map(Option::unwrap)
-                .sum()
-        })
-        .sorted_by(|a: &u32, b| Ord::cmp(b, a))
-        .take(3);
-
-    Ok(top_3.sum())
+        .map(|Reverse((total, Reverse(index)))| (index, total))
+        .collect())
 }
 
-fn main() {
-    let input = &advent_of_code::read_file("inputs", 1);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+pub fn part_one(input: &str) -> color_eyre::Result<u32> {
+    top_k_elves(input.as_bytes(), 1)?
+        .first()
+        .map(|(_, total)| *total)
+        .ok_or(eyre::eyre!("No elves in the input"))
+}
+
+pub fn part_two(input: &str) -> color_eyre::Result<u32> {
+    let top_3 = top_k_elves(input.as_bytes(), 3)?;
+    top_3
+        .iter()
+        .try_fold(0u32, |sum, (_, total)| sum.checked_add(*total))
+        .ok_or(eyre::eyre!("Total calories of the top 3 elves overflow"))
+}
+
+mod report {
+    use std::{fmt::Write, io::BufRead};
+
+    use crate::{Elf, Elves};
+
+    /// One line of the inventory report.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Row {
+        pub elf: Elf,
+        /// 1 for the elf carrying the most; elves with the same total share a rank.
+        pub rank: usize,
+        /// Share of elves carrying at most as much as this one, in percent.
+        pub percentile: f64,
+    }
+
+    /// Every elf of the inventory in input order.
+    pub fn rows(reader: impl BufRead) -> eyre::Result<Vec<Row>> {
+        let elves = Elves::new(reader).collect::<eyre::Result<Vec<_>>>()?;
+        let mut totals: Vec<u32> = elves.iter().map(|elf| elf.total).collect();
+        totals.sort_unstable();
+
+        let count = elves.len();
+        Ok(elves
+            .into_iter()
+            .map(|elf| {
+                let at_most = totals.partition_point(|total| *total <= elf.total);
+                Row {
+                    elf,
+                    rank: count - at_most + 1,
+                    percentile: 100.0 * at_most as f64 / count as f64,
+                }
+            })
+            .collect())
+    }
+
+    const HEADER: [&str; 5] = ["elf", "items", "total", "rank", "percentile"];
+
+    fn fields(row: &Row) -> [String; 5] {
+        [
+            row.elf.index.to_string(),
+            row.elf.items.to_string(),
+            row.elf.total.to_string(),
+            row.rank.to_string(),
+            format!("{:.1}", row.percentile),
+        ]
+    }
+
+    pub fn table(rows: &[Row]) -> String {
+        let lines: Vec<[String; 5]> = rows.iter().map(fields).collect();
+        let widths: Vec<usize> = (0..HEADER.len())
+            .map(|i| {
+                lines
+                    .iter()
+                    .map(|line| line[i].len())
+                    .chain([HEADER[i].len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let mut out = String::new();
+        let header = HEADER.map(String::from);
+        for line in std::iter::once(&header).chain(&lines) {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+                .collect();
+            writeln!(out, "{}", cells.join("  ")).unwrap();
+        }
+        out
+    }
+
+    pub fn csv(rows: &[Row]) -> String {
+        let mut out = HEADER.join(",") + "\n";
+        for row in rows {
+            writeln!(out, "{}", fields(row).join(",")).unwrap();
+        }
+        out
+    }
+
+    const BAR_WIDTH: usize = 40;
+
+    /// Totals grouped into at most `buckets` ranges of equal size, one bar per range.
+    pub fn histogram(rows: &[Row], buckets: usize) -> String {
+        let (Some(min), Some(max)) = (
+            rows.iter().map(|row| row.elf.total).min(),
+            rows.iter().map(|row| row.elf.total).max(),
+        ) else {
+            return String::new();
+        };
+        let span = (max - min) as usize + 1;
+        let buckets = buckets.clamp(1, span);
+        let size = span.div_ceil(buckets);
+        // rounding the size up can leave the last buckets past the max.
+        let buckets = span.div_ceil(size);
+
+        let mut counts = vec![0; buckets];
+        for row in rows {
+            counts[(row.elf.total - min) as usize / size] += 1;
+        }
+        let most = counts.iter().copied().max().unwrap_or(1);
+        let width = max.to_string().len();
+
+        let mut out = String::new();
+        for (i, count) in counts.iter().enumerate() {
+            let from = min as usize + i * size;
+            let to = (from + size - 1).min(max as usize);
+            let bar = "#".repeat((count * BAR_WIDTH).div_ceil(most));
+            writeln!(
+                out,
+                "{:>width$} - {:>width$} | {} {}",
+                from,
+                to,
+                bar,
+                count,
+                width = width
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 1);
+
+    match args.subcommand()?.as_deref() {
+        Some("report") => {
+            let rows = report::rows(input.as_bytes())?;
+            if args.contains("--csv") {
+                print!("{}", report::csv(&rows));
+            } else {
+                let buckets = args.opt_value_from_str("--buckets")?.unwrap_or(10);
+                print!("{}", report::table(&rows));
+                println!();
+                print!("{}", report::histogram(&rows, buckets));
+            }
+        }
+        Some(other) => eyre::bail!("Unknown subcommand {:?}, expected report", other),
+        None => {
+            advent_of_code::solve!(1, part_one, input);
+            advent_of_code::solve!(2, part_two, input);
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -77,4 +277,70 @@ mod tests {
         let input = advent_of_code::read_file("examples", 1);
         assert_eq!(part_two(&input).unwrap(), 45000);
     }
+
+    #[test]
+    fn test_top_k_elves() {
+        let input = advent_of_code::read_file("examples", 1);
+        assert_eq!(
+            top_k_elves(input.as_bytes(), 3).unwrap(),
+            vec![(4, 24000), (3, 11000), (5, 10000)]
+        );
+        assert_eq!(top_k_elves(input.as_bytes(), 10).unwrap().len(), 5);
+        assert_eq!(top_k_elves(input.as_bytes(), 0).unwrap(), vec![]);
+        // ties keep the first elf
+        assert_eq!(
+            top_k_elves("5\n\n\n2\n3\n".as_bytes(), 1).unwrap(),
+            vec![(1, 5)]
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let input = advent_of_code::read_file("examples", 1);
+        let rows = report::rows(input.as_bytes()).unwrap();
+        assert_eq!(
+            report::csv(&rows),
+            "elf,items,total,rank,percentile\n\
+             1,3,6000,4,40.0\n\
+             2,1,4000,5,20.0\n\
+             3,2,11000,2,80.0\n\
+             4,3,24000,1,100.0\n\
+             5,1,10000,3,60.0\n"
+        );
+        assert_eq!(
+            report::table(&rows).lines().take(2).collect::<Vec<_>>(),
+            vec![
+                "elf  items  total  rank  percentile",
+                "  1      3   6000     4        40.0"
+            ]
+        );
+        assert_eq!(
+            report::histogram(&rows, 2),
+            " 4000 - 14000 | ######################################## 4\n\
+             14001 - 24000 | ########## 1\n"
+        );
+
+        let nine: Vec<String> = (1..=9).map(|total| total.to_string()).collect();
+        let nine = nine.join("\n\n");
+        let rows = report::rows(nine.as_bytes()).unwrap();
+        let bar = "#".repeat(40);
+        assert_eq!(
+            report::histogram(&rows, 4),
+            format!("1 - 3 | {bar} 3\n4 - 6 | {bar} 3\n7 - 9 | {bar} 3\n")
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let e = part_one("1000\n\n2x00\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 3: \"2x00\"");
+        let e = part_two("4000000000\n400000000\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 2: \"400000000\": Total calories overflow"
+        );
+        let e = part_two("4000000000\n\n4000000000\n").unwrap_err();
+        assert_eq!(e.to_string(), "Total calories of the top 3 elves overflow");
+        assert!(part_one("").is_err());
+    }
 }
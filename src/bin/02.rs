@@ -1,93 +1,183 @@
-use std::str::FromStr;
-
+use advent_of_code::helpers::error::{invalid, parse_lines};
 use eyre::eyre;
+use itertools::Itertools;
+
+/// A move, identified by its position in the [`Ruleset`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct PlayType(usize);
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-enum PlayType {
-    Rock,
-    Paper,
-    Scissors,
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Outcome {
+    Lose,
+    Draw,
+    Win,
 }
 
-fn parse_opponent(s: &str) -> PlayType {
+fn parse_outcome(s: &str) -> eyre::Result<Outcome> {
     match s {
-        "A" => PlayType::Rock,
-        "B" => PlayType::Paper,
-        "C" => PlayType::Scissors,
-        _ => panic!("Invalid opponent play type"),
+        "X" => Ok(Outcome::Lose),
+        "Y" => Ok(Outcome::Draw),
+        "Z" => Ok(Outcome::Win),
+        _ => Err(invalid("outcome", s, "X, Y or Z")),
     }
 }
 
-fn parse_mine(s: &str) -> PlayType {
-    match s {
-        "X" => PlayType::Rock,
-        "Y" => PlayType::Paper,
-        "Z" => PlayType::Scissors,
-        _ => panic!("Invalid mine play type"),
-    }
+#[derive(Debug, Clone)]
+struct Move {
+    name: String,
+    score: u32,
+    /// Symbol of the move in the first column of the strategy guide.
+    opponent_symbol: char,
+    /// Symbol of the move in the second column when it is read as a move.
+    symbol: char,
 }
 
-fn parse_outcome(s: &str) -> Outcome {
-    match s {
-        "X" => Outcome::Lose,
-        "Y" => Outcome::Draw,
-        "Z" => Outcome::Win,
-        _ => panic!("Invalid outcome type"),
+/// The moves of a hand game, which of them beats which, and how rounds are scored.
+#[derive(Debug, Clone)]
+struct Ruleset {
+    moves: Vec<Move>,
+    /// `beats[i][j]` is true when move `i` beats move `j`.
+    beats: Vec<Vec<bool>>,
+    /// Points for losing, drawing and winning a round, in that order.
+    outcome_scores: [u32; 3],
+}
+
+/// "A, B or C"
+fn one_of(symbols: impl Iterator<Item = char>) -> String {
+    let symbols: Vec<String> = symbols.map(String::from).collect();
+    match symbols.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => "nothing".to_owned(),
     }
 }
 
-impl PlayType {
-    pub fn win_against(p: &Self) -> Self {
-        match p {
-            Self::Rock => Self::Paper,
-            Self::Paper => Self::Scissors,
-            Self::Scissors => Self::Rock,
+impl Ruleset {
+    pub fn new(
+        moves: Vec<Move>,
+        beats: Vec<Vec<bool>>,
+        outcome_scores: [u32; 3],
+    ) -> eyre::Result<Self> {
+        let n = moves.len();
+        if beats.len() != n || beats.iter().any(|row| row.len() != n) {
+            return Err(eyre!("Expected a {}x{} beats matrix", n, n));
         }
+        for i in 0..n {
+            for j in 0..n {
+                if beats[i][j] && (i == j || beats[j][i]) {
+                    return Err(eyre!(
+                        "{} and {} can't beat each other",
+                        moves[i].name,
+                        moves[j].name
+                    ));
+                }
+            }
+        }
+        Ok(Self {
+            moves,
+            beats,
+            outcome_scores,
+        })
+    }
+
+    /// A game where every move beats the moves an odd number of places before it, wrapping
+    /// around. Moves score 1, 2, 3, ... in the given order; the opponent plays them as `A`, `B`,
+    /// ... and the guide suggests them with the last letters up to `Z`.
+    pub fn cyclic(names: &[&str]) -> eyre::Result<Self> {
+        let n = names.len();
+        if n.is_multiple_of(2) {
+            return Err(eyre!(
+                "A cyclic game needs an odd number of moves, got {}",
+                n
+            ));
+        }
+        if n > 26 {
+            return Err(eyre!(
+                "A cyclic game can have at most 26 moves, one per letter, got {}",
+                n
+            ));
+        }
+        let moves = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Move {
+                name: name.to_string(),
+                score: i as u32 + 1,
+                opponent_symbol: (b'A' + i as u8) as char,
+                symbol: (b'Z' - (n - 1 - i) as u8) as char,
+            })
+            .collect();
+        let beats = (0..n)
+            .map(|i| (0..n).map(|j| (i + n - j) % n % 2 == 1).collect())
+            .collect();
+        Self::new(moves, beats, [0, 3, 6])
+    }
+
+    pub fn rock_paper_scissors() -> eyre::Result<Self> {
+        Self::cyclic(&["Rock", "Paper", "Scissors"])
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> eyre::Result<Self> {
+        Self::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"])
+    }
+
+    pub fn by_name(name: &str) -> eyre::Result<Self> {
+        match name {
+            "rps" => Self::rock_paper_scissors(),
+            "rpsls" => Self::rock_paper_scissors_lizard_spock(),
+            _ => Err(invalid("ruleset", name, "rps or rpsls")),
+        }
+    }
+
+    fn moves(&self) -> impl Iterator<Item = PlayType> {
+        (0..self.moves.len()).map(PlayType)
+    }
+
+    pub fn parse_opponent(&self, s: &str) -> eyre::Result<PlayType> {
+        self.moves()
+            .find(|p| s.len() == 1 && s.starts_with(self.moves[p.0].opponent_symbol))
+            .ok_or_else(|| {
+                let expected = one_of(self.moves.iter().map(|m| m.opponent_symbol));
+                invalid("opponent play", s, &expected)
+            })
     }
 
-    pub fn play(&self, opponent: &Self) -> Outcome {
-        if Self::win_against(opponent) == *self {
+    pub fn parse_mine(&self, s: &str) -> eyre::Result<PlayType> {
+        self.moves()
+            .find(|p| s.len() == 1 && s.starts_with(self.moves[p.0].symbol))
+            .ok_or_else(|| invalid("play", s, &one_of(self.moves.iter().map(|m| m.symbol))))
+    }
+
+    pub fn play(&self, mine: PlayType, opponent: PlayType) -> Outcome {
+        if self.beats[mine.0][opponent.0] {
             Outcome::Win
-        } else if Self::win_against(self) == *opponent {
+        } else if self.beats[opponent.0][mine.0] {
             Outcome::Lose
         } else {
             Outcome::Draw
         }
     }
 
-    pub fn score(&self) -> u32 {
-        match self {
-            PlayType::Rock => 1,
-            PlayType::Paper => 2,
-            PlayType::Scissors => 3,
-        }
+    /// The highest scoring move that ends the round against `opponent` with `outcome`.
+    pub fn cheat(&self, outcome: Outcome, opponent: PlayType) -> eyre::Result<PlayType> {
+        self.moves()
+            .filter(|mine| self.play(*mine, opponent) == outcome)
+            .max_by_key(|mine| self.moves[mine.0].score)
+            .ok_or_else(|| {
+                eyre!(
+                    "No move gives {:?} against {}",
+                    outcome,
+                    self.moves[opponent.0].name
+                )
+            })
     }
-}
 
-#[derive(PartialEq, Eq, Copy, Clone)]
-enum Outcome {
-    Win,
-    Draw,
-    Lose,
-}
-
-impl Outcome {
-    pub fn cheat(&self, opponent: &PlayType) -> PlayType {
-        let win = PlayType::win_against(opponent);
-        let lose = PlayType::win_against(&win);
-        match self {
-            Self::Win => win,
-            Self::Draw => *opponent,
-            Self::Lose => lose,
-        }
+    pub fn move_score(&self, p: PlayType) -> u32 {
+        self.moves[p.0].score
     }
 
-    pub fn score(&self) -> u32 {
-        match self {
-            Outcome::Win => 6,
-            Outcome::Draw => 3,
-            Outcome::Lose => 0,
-        }
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome_scores[outcome as usize]
     }
 }
 
@@ -97,89 +187,220 @@ struct CheatRound {
 }
 
 impl CheatRound {
-    pub fn get_round(&self) -> Round {
-        let mine = self.outcome.cheat(&self.opponent);
+    pub fn parse(s: &str, rules: &Ruleset) -> eyre::Result<Self> {
+        let mut iter = s.split_whitespace();
+        let opponent = rules.parse_opponent(iter.next().ok_or(eyre!("Opponent play expected"))?)?;
+        let outcome = parse_outcome(iter.next().ok_or(eyre!("Outcome expected"))?)?;
+        Ok(CheatRound { outcome, opponent })
+    }
+
+    pub fn get_round(&self, rules: &Ruleset) -> eyre::Result<Round> {
+        let mine = rules.cheat(self.outcome, self.opponent)?;
 
-        Round {
+        Ok(Round {
             mine,
             opponent: self.opponent,
-        }
+        })
     }
 }
 
-impl FromStr for CheatRound {
-    type Err = color_eyre::Report;
+struct Round {
+    mine: PlayType,
+    opponent: PlayType,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Round {
+    pub fn parse(s: &str, rules: &Ruleset) -> eyre::Result<Self> {
         let mut iter = s.split_whitespace();
-        let opponent = iter
-            .next()
-            .map(parse_opponent)
-            .ok_or(eyre!("Opponent play expected"))?;
-        let outcome = iter
-            .next()
-            .map(parse_outcome)
-            .ok_or(eyre!("Outcome expected"))?;
-        Ok(CheatRound { outcome, opponent })
+        let opponent = rules.parse_opponent(iter.next().ok_or(eyre!("Opponent play expected"))?)?;
+        let mine = rules.parse_mine(iter.next().ok_or(eyre!("Mine play expected"))?)?;
+        Ok(Round { mine, opponent })
+    }
+
+    pub fn score(&self, rules: &Ruleset) -> u32 {
+        rules.move_score(self.mine) + rules.outcome_score(rules.play(self.mine, self.opponent))
     }
 }
 
-struct Round {
-    mine: PlayType,
-    opponent: PlayType,
+/// Reads the second column of the guide as my move.
+fn play_guide(input: &str, rules: &Ruleset) -> eyre::Result<u32> {
+    let rounds = parse_lines(input, |line| Round::parse(line, rules))?;
+    Ok(rounds.iter().map(|round| round.score(rules)).sum())
 }
 
-impl Round {
-    pub fn score(&self) -> u32 {
-        self.mine.score() + self.mine.play(&self.opponent).score()
+/// Reads the second column of the guide as the outcome to aim for.
+fn cheat_guide(input: &str, rules: &Ruleset) -> eyre::Result<u32> {
+    let rounds = parse_lines(input, |line| {
+        CheatRound::parse(line, rules)?.get_round(rules)
+    })?;
+    Ok(rounds.iter().map(|round| round.score(rules)).sum())
+}
+
+/// A meaning for the symbols in the second column of the guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Interpretation {
+    Moves(Vec<(char, PlayType)>),
+    Outcomes(Vec<(char, Outcome)>),
+}
+
+impl Interpretation {
+    pub fn describe(&self, rules: &Ruleset) -> String {
+        match self {
+            Self::Moves(mapping) => mapping
+                .iter()
+                .map(|(symbol, p)| format!("{}={}", symbol, rules.moves[p.0].name))
+                .join(" "),
+            Self::Outcomes(mapping) => mapping
+                .iter()
+                .map(|(symbol, outcome)| format!("{}={:?}", symbol, outcome))
+                .join(" "),
+        }
     }
 }
 
-impl FromStr for Round {
-    type Err = color_eyre::Report;
+/// The score of every interpretation of a guide.
+struct Analysis {
+    scores: Vec<(Interpretation, u32)>,
+    /// Readings of the guide that were left out, and why.
+    skipped: Vec<String>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split_whitespace();
-        let opponent = iter
-            .next()
-            .map(parse_opponent)
-            .ok_or(eyre!("Opponent play expected"))?;
-        let mine = iter
-            .next()
-            .map(parse_mine)
-            .ok_or(eyre!("Mine play expected"))?;
-        Ok(Round { mine, opponent })
+impl Analysis {
+    /// The highest scoring interpretation, the first one on ties.
+    pub fn max(&self) -> Option<&(Interpretation, u32)> {
+        self.scores.iter().rev().max_by_key(|(_, score)| *score)
+    }
+
+    /// The lowest scoring interpretation, the first one on ties.
+    pub fn min(&self) -> Option<&(Interpretation, u32)> {
+        self.scores.iter().min_by_key(|(_, score)| *score)
     }
 }
 
-pub fn part_one(input: &str) -> color_eyre::Result<u32> {
-    let mut total_score: u32 = 0;
-    for line in input.split("\n") {
-        if !line.trim().is_empty() {
-            let round: Round = Round::from_str(line)?;
-            total_score += round.score();
+/// Scores the guide for every way of reading the symbols of its second column as distinct moves,
+/// and as distinct outcomes.
+fn analyze(input: &str, rules: &Ruleset) -> eyre::Result<Analysis> {
+    let guide = parse_lines(input, |line| {
+        let mut iter = line.split_whitespace();
+        let opponent = rules.parse_opponent(iter.next().ok_or(eyre!("Opponent play expected"))?)?;
+        let symbol = iter.next().ok_or(eyre!("Symbol expected"))?;
+        match symbol.chars().exactly_one() {
+            Ok(symbol) => Ok((opponent, symbol)),
+            Err(_) => Err(invalid("symbol", symbol, "a single letter")),
         }
+    })?;
+    let symbols: Vec<char> = guide
+        .iter()
+        .map(|(_, symbol)| *symbol)
+        .sorted()
+        .dedup()
+        .collect();
+    if symbols.len() > rules.moves.len() {
+        return Err(eyre!(
+            "The guide uses {} symbols, but there are only {} moves",
+            symbols.len(),
+            rules.moves.len()
+        ));
+    }
+
+    let as_moves = rules
+        .moves()
+        .permutations(symbols.len())
+        .map(|moves| Interpretation::Moves(symbols.iter().copied().zip(moves).collect()));
+    let outcomes = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+    let mut skipped = vec![];
+    if symbols.len() > outcomes.len() {
+        skipped.push(format!(
+            "The guide uses {} symbols, but there are only {} outcomes, so it can't be read as outcomes",
+            symbols.len(),
+            outcomes.len()
+        ));
     }
-    Ok(total_score)
+    let as_outcomes = outcomes
+        .into_iter()
+        .permutations(symbols.len())
+        .map(|outcomes| Interpretation::Outcomes(symbols.iter().copied().zip(outcomes).collect()));
+
+    let scores = as_moves
+        .chain(as_outcomes)
+        .map(|interpretation| {
+            let mut total = 0;
+            for (opponent, symbol) in &guide {
+                let round = match &interpretation {
+                    Interpretation::Moves(mapping) => {
+                        let (_, mine) = mapping.iter().find(|(s, _)| s == symbol).unwrap();
+                        Round {
+                            mine: *mine,
+                            opponent: *opponent,
+                        }
+                    }
+                    Interpretation::Outcomes(mapping) => {
+                        let (_, outcome) = mapping.iter().find(|(s, _)| s == symbol).unwrap();
+                        CheatRound {
+                            opponent: *opponent,
+                            outcome: *outcome,
+                        }
+                        .get_round(rules)?
+                    }
+                };
+                total += round.score(rules);
+            }
+            Ok((interpretation, total))
+        })
+        .collect::<eyre::Result<_>>()?;
+    Ok(Analysis { scores, skipped })
+}
+
+pub fn part_one(input: &str) -> color_eyre::Result<u32> {
+    play_guide(input, &Ruleset::rock_paper_scissors()?)
 }
 
 pub fn part_two(input: &str) -> color_eyre::Result<u32> {
-    let mut total_score: u32 = 0;
-    for line in input.split("\n") {
-        if !line.trim().is_empty() {
-            let cheat_round: CheatRound = CheatRound::from_str(line)?;
-            let round = cheat_round.get_round();
-            total_score += round.score();
-        }
-    }
-    Ok(total_score)
+    cheat_guide(input, &Ruleset::rock_paper_scissors()?)
 }
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    let input = &advent_of_code::read_file("inputs", 2);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 2);
+    let rules = args.opt_value_from_str::<_, String>("--rules")?;
+
+    if args.subcommand()?.as_deref() == Some("analyze") {
+        let rules = Ruleset::by_name(rules.as_deref().unwrap_or("rps"))?;
+        let analysis = analyze(input, &rules)?;
+        for (interpretation, score) in &analysis.scores {
+            println!("{:>8}  {}", score, interpretation.describe(&rules));
+        }
+        if let (Some((max, max_score)), Some((min, min_score))) = (analysis.max(), analysis.min()) {
+            println!();
+            println!("max {:>8}  {}", max_score, max.describe(&rules));
+            println!("min {:>8}  {}", min_score, min.describe(&rules));
+        }
+        for reason in &analysis.skipped {
+            println!();
+            println!("{}", reason);
+        }
+        return Ok(());
+    }
+
+    match rules {
+        Some(name) => {
+            let rules = Ruleset::by_name(&name)?;
+            let play = |input: &str| play_guide(input, &rules);
+            let cheat = |input: &str| cheat_guide(input, &rules);
+            advent_of_code::solve!(1, play, input);
+            advent_of_code::solve!(2, cheat, input);
+        }
+        None => {
+            advent_of_code::solve!(1, part_one, input);
+            advent_of_code::solve!(2, part_two, input);
+        }
+    }
     Ok(())
 }
 
@@ -198,4 +419,103 @@ mod tests {
         let input = advent_of_code::read_file("examples", 2);
         assert_eq!(part_two(&input).unwrap(), 12);
     }
+
+    #[test]
+    fn test_rulesets() {
+        let rps = Ruleset::rock_paper_scissors().unwrap();
+        let (rock, paper, scissors) = (PlayType(0), PlayType(1), PlayType(2));
+        assert_eq!(rps.play(paper, rock), Outcome::Win);
+        assert_eq!(rps.play(rock, scissors), Outcome::Win);
+        assert_eq!(rps.play(rock, paper), Outcome::Lose);
+        assert_eq!(rps.cheat(Outcome::Win, scissors).unwrap(), rock);
+
+        let rpsls = Ruleset::rock_paper_scissors_lizard_spock().unwrap();
+        let names = |p: PlayType| rpsls.moves[p.0].name.as_str();
+        let beaten_by = |name: &str| -> Vec<&str> {
+            let p = rpsls.moves().find(|p| names(*p) == name).unwrap();
+            rpsls
+                .moves()
+                .filter(|q| rpsls.play(p, *q) == Outcome::Win)
+                .map(names)
+                .collect()
+        };
+        assert_eq!(beaten_by("Rock"), vec!["Scissors", "Lizard"]);
+        assert_eq!(beaten_by("Paper"), vec!["Rock", "Spock"]);
+        assert_eq!(beaten_by("Scissors"), vec!["Paper", "Lizard"]);
+        assert_eq!(beaten_by("Spock"), vec!["Rock", "Scissors"]);
+        assert_eq!(beaten_by("Lizard"), vec!["Paper", "Spock"]);
+
+        // Spock (Y) beats Rock (A), then Lizard (Z) beats Spock (D).
+        assert_eq!(play_guide("A Y\nD Z\n", &rpsls).unwrap(), 4 + 6 + 5 + 6);
+        // both Paper (2) and Spock (4) beat Rock, Spock scores more.
+        assert_eq!(cheat_guide("A Z\n", &rpsls).unwrap(), 4 + 6);
+
+        let e = Ruleset::cyclic(&["Rock", "Paper"]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "A cyclic game needs an odd number of moves, got 2"
+        );
+        let e = Ruleset::cyclic(&["Move"; 27]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "A cyclic game can have at most 26 moves, one per letter, got 27"
+        );
+        assert!(Ruleset::new(
+            rps.moves.clone(),
+            vec![vec![true, false, false]; 3],
+            [0, 3, 6]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_analyze() {
+        let input = advent_of_code::read_file("examples", 2);
+        let rules = Ruleset::rock_paper_scissors().unwrap();
+        let analysis = analyze(&input, &rules).unwrap();
+        assert_eq!(analysis.scores.len(), 12);
+
+        let describe = |(interpretation, score): &(Interpretation, u32)| {
+            (interpretation.describe(&rules), *score)
+        };
+        let scores: Vec<(String, u32)> = analysis.scores.iter().map(describe).collect();
+        assert!(scores.contains(&("X=Rock Y=Paper Z=Scissors".to_owned(), 15)));
+        assert!(scores.contains(&("X=Lose Y=Draw Z=Win".to_owned(), 12)));
+        assert_eq!(
+            describe(analysis.max().unwrap()),
+            ("X=Scissors Y=Paper Z=Rock".to_owned(), 24)
+        );
+        assert_eq!(
+            describe(analysis.min().unwrap()),
+            ("X=Rock Y=Scissors Z=Paper".to_owned(), 6)
+        );
+
+        assert!(analysis.skipped.is_empty());
+
+        assert!(analyze("A Y\nB XX\n", &rules).is_err());
+
+        let rpsls = Ruleset::rock_paper_scissors_lizard_spock().unwrap();
+        let analysis = analyze("A V\nA W\nA X\nA Y\n", &rpsls).unwrap();
+        assert_eq!(analysis.scores.len(), 5 * 4 * 3 * 2);
+        assert!(analysis
+            .scores
+            .iter()
+            .all(|(interpretation, _)| matches!(interpretation, Interpretation::Moves(_))));
+        assert_eq!(
+            analysis.skipped,
+            vec![
+                "The guide uses 4 symbols, but there are only 3 outcomes, so it can't be read as outcomes"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let e = part_one("A Y\nQ X\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 2: \"Q X\": Invalid opponent play \"Q\", expected A, B or C"
+        );
+        assert!(part_two("A\n").is_err());
+    }
 }
@@ -1,118 +1,423 @@
-use itertools::Itertools;
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    fmt::{Display, Write},
+    ops::{BitAnd, BitOr},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-struct RuckSack(Compartment, Compartment);
+use advent_of_code::helpers::error::{invalid, parse_lines, WithLine};
+
+/// Set of item types, one bit per priority: bit 0 is `a`, bit 51 is `Z`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct ItemSet(u64);
+
+fn priority(c: char) -> color_eyre::Result<u32> {
+    match c {
+        'a'..='z' => Ok(c as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Ok(c as u32 - 'A' as u32 + 27),
+        _ => Err(invalid("item", c, "a to z or A to Z")),
+    }
+}
+
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        _ => (b'A' + (priority - 27) as u8) as char,
+    }
+}
+
+impl ItemSet {
+    pub const ALL: Self = Self((1 << 52) - 1);
+
+    pub fn insert(&mut self, c: char) -> color_eyre::Result<()> {
+        self.0 |= 1 << (priority(c)? - 1);
+        Ok(())
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Priorities of the items, lowest first.
+    pub fn priorities(&self) -> impl Iterator<Item = u32> {
+        let bits = self.0;
+        (1..=52).filter(move |p| bits & (1 << (p - 1)) != 0)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = char> {
+        self.priorities().map(item)
+    }
+
+    pub fn priority_sum(&self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl FromStr for ItemSet {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = Self::default();
+        for c in s.chars() {
+            set.insert(c)?;
+        }
+        Ok(set)
+    }
+}
+
+impl Display for ItemSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.items().try_for_each(|c| write!(f, "{}", c))
+    }
+}
+
+struct RuckSack(ItemSet, ItemSet);
 
 impl FromStr for RuckSack {
     type Err = color_eyre::Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() % 2 == 1 {
+        // checked before splitting, which counts bytes.
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            Err(invalid("item", c, "a to z or A to Z"))
+        } else if s.len() % 2 == 1 {
             Err(eyre::eyre!("Invalid RuckSack length: {}", s.len()))
         } else {
             let (first, second) = s.split_at(s.len() / 2);
-
-            let c1 = Compartment::from_str(first)?;
-            let c2 = Compartment::from_str(second)?;
-            Ok(RuckSack(c1, c2))
+            Ok(RuckSack(first.parse()?, second.parse()?))
         }
     }
 }
 
 impl RuckSack {
-    pub fn all_item_types(&self) -> HashSet<char> {
-        let Self(first, second) = self;
-        first.0.union(&second.0).map(ToOwned::to_owned).collect()
+    pub fn all_item_types(&self) -> ItemSet {
+        self.0 | self.1
     }
-    pub fn common(&self) -> Vec<char> {
-        let Self(first, second) = self;
-        first
-            .0
-            .intersection(&second.0)
-            .map(ToOwned::to_owned)
-            .collect()
+
+    pub fn common(&self) -> ItemSet {
+        self.0 & self.1
     }
 }
 
-fn priority(c: &char) -> color_eyre::Result<u32> {
-    match c {
-        'A'..='Z' => Ok(u32::try_from(*c)? - u32::try_from('A')? + 27),
-        'a'..='z' => Ok(u32::try_from(*c)? - u32::try_from('a')? + 1),
-        _ => Err(eyre::eyre!("Invalid character: {}", c)),
+/// The badge of every group of `group_size` consecutive rucksacks.
+fn badges(input: &str, group_size: usize) -> color_eyre::Result<Vec<ItemSet>> {
+    if group_size == 0 {
+        eyre::bail!("Groups need at least one elf");
+    }
+    let rucksacks = parse_lines(input, RuckSack::from_str)?;
+    let trailing = rucksacks.len() % group_size;
+    if trailing != 0 {
+        eyre::bail!(
+            "Incomplete group {}: {} of {} rucksacks",
+            rucksacks.len() / group_size + 1,
+            trailing,
+            group_size
+        );
     }
+    rucksacks
+        .chunks(group_size)
+        .enumerate()
+        .map(|(i, group)| {
+            let common = group
+                .iter()
+                .fold(ItemSet::ALL, |common, r| common & r.all_item_types());
+            match common.len() {
+                1 => Ok(common),
+                0 => Err(eyre::eyre!(
+                    "Fail invariant: No common type in group {}",
+                    i + 1
+                )),
+                _ => Err(eyre::eyre!(
+                    "Fail invariant: More than 1 common type in group {}",
+                    i + 1
+                )),
+            }
+        })
+        .collect()
 }
 
-struct Compartment(HashSet<char>);
+fn badge_total(input: &str, group_size: usize) -> color_eyre::Result<u32> {
+    Ok(badges(input, group_size)?
+        .iter()
+        .map(ItemSet::priority_sum)
+        .sum())
+}
 
-impl FromStr for Compartment {
-    type Err = color_eyre::Report;
+pub fn part_one(input: &str) -> color_eyre::Result<u32> {
+    let rucksacks = parse_lines(input, RuckSack::from_str)?;
+    Ok(rucksacks.iter().map(|r| r.common().priority_sum()).sum())
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let h: HashSet<char> = HashSet::from_iter(s.chars());
-        Ok(Compartment(h))
-    }
+pub fn part_two(input: &str) -> color_eyre::Result<u32> {
+    badge_total(input, 3)
 }
 
-pub fn part_one(input: &str) -> color_eyre::Result<u32> {
-    let mut total = 0;
-    for line in input.lines() {
-        let rucksack = RuckSack::from_str(line)?;
-        let v: u32 = rucksack
-            .common()
-            .iter()
-            .map(priority)
-            .collect::<color_eyre::Result<Vec<u32>>>()?
-            .iter()
-            .sum();
-        total += v;
+/// "p (16), r (18)"
+fn describe_items(set: ItemSet) -> String {
+    set.priorities()
+        .map(|p| format!("{} ({})", item(p), p))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Every rucksack with its shared items, and every group of `group_size` with its badge. Groups
+/// without exactly one badge are listed with what they have in common instead of failing.
+fn explain(input: &str, group_size: usize) -> color_eyre::Result<String> {
+    if group_size == 0 {
+        eyre::bail!("Groups need at least one elf");
     }
-    Ok(total)
+    let rucksacks = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            Ok((
+                i + 1,
+                line,
+                RuckSack::from_str(line).with_line(i + 1, line)?,
+            ))
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let mut out = String::new();
+    for (number, line, rucksack) in &rucksacks {
+        let (first, second) = line.split_at(line.len() / 2);
+        let shared = match rucksack.common().len() {
+            0 => "nothing shared".to_owned(),
+            _ => format!("shared {}", describe_items(rucksack.common())),
+        };
+        writeln!(out, "line {}: {} {} | {}", number, first, second, shared)?;
+    }
+
+    writeln!(out)?;
+    for (i, group) in rucksacks.chunks(group_size).enumerate() {
+        let (first, last) = (group[0].0, group[group.len() - 1].0);
+        write!(out, "group {} (lines {}-{}): ", i + 1, first, last)?;
+        let common = group.iter().fold(ItemSet::ALL, |common, (_, _, r)| {
+            common & r.all_item_types()
+        });
+        if group.len() < group_size {
+            writeln!(
+                out,
+                "incomplete, {} of {} rucksacks",
+                group.len(),
+                group_size
+            )?;
+        } else if common.len() == 1 {
+            writeln!(out, "badge {}", describe_items(common))?;
+        } else if common.len() == 0 {
+            writeln!(out, "no common item")?;
+        } else {
+            writeln!(
+                out,
+                "{} common items: {}",
+                common.len(),
+                describe_items(common)
+            )?;
+        }
+    }
+    Ok(out)
 }
 
-pub fn part_two(input: &str) -> color_eyre::Result<u32> {
-    let mut total = 0;
-    let mut it = input.lines().peekable();
-    loop {
-        if it.peek().is_none() {
-            break;
+/// The first implementation, kept to benchmark the bitmask version against.
+mod hashset {
+    use std::{collections::HashSet, str::FromStr};
+
+    struct RuckSack(Compartment, Compartment);
+
+    impl FromStr for RuckSack {
+        type Err = color_eyre::Report;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            if s.len() % 2 == 1 {
+                Err(eyre::eyre!("Invalid RuckSack length: {}", s.len()))
+            } else {
+                let (first, second) = s.split_at(s.len() / 2);
+
+                let c1 = Compartment::from_str(first)?;
+                let c2 = Compartment::from_str(second)?;
+                Ok(RuckSack(c1, c2))
+            }
         }
+    }
 
-        let s1 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
-        let s2 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
-        let s3 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
-
-        let common: HashSet<char> = s3
-            .all_item_types()
-            .intersection(
-                &s1.all_item_types()
-                    .intersection(&s2.all_item_types())
-                    .map(ToOwned::to_owned)
-                    .collect(),
-            )
-            .map(ToOwned::to_owned)
-            .collect();
-
-        if common.len() != 1 {
-            Err(eyre::eyre!(
-                "Fail invariant: More than 1 common type ingroup"
-            ))?
+    impl RuckSack {
+        pub fn all_item_types(&self) -> HashSet<char> {
+            let Self(first, second) = self;
+            first.0.union(&second.0).map(ToOwned::to_owned).collect()
         }
-        total += priority(
-            common
+        pub fn common(&self) -> Vec<char> {
+            let Self(first, second) = self;
+            first
+                .0
+                .intersection(&second.0)
+                .map(ToOwned::to_owned)
+                .collect()
+        }
+    }
+
+    fn priority(c: &char) -> color_eyre::Result<u32> {
+        match c {
+            'A'..='Z' => Ok(u32::from(*c) - u32::from('A') + 27),
+            'a'..='z' => Ok(u32::from(*c) - u32::from('a') + 1),
+            _ => Err(eyre::eyre!("Invalid character: {}", c)),
+        }
+    }
+
+    struct Compartment(HashSet<char>);
+
+    impl FromStr for Compartment {
+        type Err = color_eyre::Report;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let h: HashSet<char> = HashSet::from_iter(s.chars());
+            Ok(Compartment(h))
+        }
+    }
+
+    pub fn part_one(input: &str) -> color_eyre::Result<u32> {
+        let mut total = 0;
+        for line in input.lines() {
+            let rucksack = RuckSack::from_str(line)?;
+            let v: u32 = rucksack
+                .common()
                 .iter()
-                .next()
-                .ok_or(eyre::eyre!("Fail invariant: No common type in group"))?,
-        )?;
+                .map(priority)
+                .collect::<color_eyre::Result<Vec<u32>>>()?
+                .iter()
+                .sum();
+            total += v;
+        }
+        Ok(total)
     }
 
-    Ok(total)
+    pub fn part_two(input: &str) -> color_eyre::Result<u32> {
+        let mut total = 0;
+        let mut it = input.lines().peekable();
+        loop {
+            if it.peek().is_none() {
+                break;
+            }
+
+            let s1 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
+            let s2 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
+            let s3 = RuckSack::from_str(it.next().ok_or(eyre::eyre!("Missing expected line"))?)?;
+
+            let common: HashSet<char> = s3
+                .all_item_types()
+                .intersection(
+                    &s1.all_item_types()
+                        .intersection(&s2.all_item_types())
+                        .map(ToOwned::to_owned)
+                        .collect(),
+                )
+                .map(ToOwned::to_owned)
+                .collect();
+
+            if common.len() != 1 {
+                Err(eyre::eyre!(
+                    "Fail invariant: More than 1 common type ingroup"
+                ))?
+            }
+            total += priority(
+                common
+                    .iter()
+                    .next()
+                    .ok_or(eyre::eyre!("Fail invariant: No common type in group"))?,
+            )?;
+        }
+
+        Ok(total)
+    }
+}
+
+type Solver = fn(&str) -> color_eyre::Result<u32>;
+
+/// Runs `f` `iterations` times, returning its answer and the mean time per run.
+fn time(f: Solver, input: &str, iterations: u32) -> color_eyre::Result<(u32, Duration)> {
+    let mut answer = f(input)?;
+    let timer = Instant::now();
+    for _ in 0..iterations {
+        answer = std::hint::black_box(f(std::hint::black_box(input))?);
+    }
+    Ok((answer, timer.elapsed() / iterations.max(1)))
+}
+
+fn bench(input: &str, iterations: u32) -> color_eyre::Result<()> {
+    let parts: [(u8, Solver, Solver); 2] = [
+        (1, part_one, hashset::part_one),
+        (2, part_two, hashset::part_two),
+    ];
+    for (part, bitmask, hashset) in parts {
+        let (answer, bitmask_time) = time(bitmask, input, iterations)?;
+        let (expected, hashset_time) = time(hashset, input, iterations)?;
+        if answer != expected {
+            eyre::bail!(
+                "Part {}: bitmask answer {} differs from HashSet answer {}",
+                part,
+                answer,
+                expected
+            );
+        }
+        println!(
+            "Part {}: {} | bitmask {:.2?} | HashSet {:.2?} | {:.1}x",
+            part,
+            answer,
+            bitmask_time,
+            hashset_time,
+            hashset_time.as_secs_f64() / bitmask_time.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+    Ok(())
 }
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    let input = &advent_of_code::read_file("inputs", 3);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 3);
+
+    let group_size = args.opt_value_from_str::<_, usize>("--group-size")?;
+
+    match args.subcommand()?.as_deref() {
+        Some("explain") => print!("{}", explain(input, group_size.unwrap_or(3))?),
+        Some("bench") => {
+            let iterations = args.opt_value_from_str("--iterations")?.unwrap_or(1000);
+            bench(input, iterations)?;
+        }
+        Some(other) => eyre::bail!("Unknown subcommand {:?}, expected explain or bench", other),
+        None => match group_size {
+            Some(group_size) => {
+                let badges = |input: &str| badge_total(input, group_size);
+                advent_of_code::solve!(1, part_one, input);
+                advent_of_code::solve!(2, badges, input);
+            }
+            None => {
+                advent_of_code::solve!(1, part_one, input);
+                advent_of_code::solve!(2, part_two, input);
+            }
+        },
+    }
     Ok(())
 }
 
@@ -131,4 +436,74 @@ mod tests {
         let input = advent_of_code::read_file("examples", 3);
         assert_eq!(part_two(&input).unwrap(), 70);
     }
+
+    #[test]
+    fn test_item_set() {
+        let set: ItemSet = "vJrwpWtwJgWr".parse().unwrap();
+        assert_eq!(set.to_string(), "gprtvwJW");
+        assert_eq!(set.len(), 8);
+        let common = set & "hcsFMMfFFhFp".parse().unwrap();
+        assert_eq!(common.items().collect::<Vec<_>>(), vec!['p']);
+        assert_eq!(common.priority_sum(), 16);
+        assert_eq!(ItemSet::ALL.len(), 52);
+        assert_eq!(ItemSet::ALL.priorities().map(item).last(), Some('Z'));
+        assert!("ab1".parse::<ItemSet>().is_err());
+
+        let e = part_one("abab\naéb\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 2: \"aéb\": Invalid item 'é', expected a to z or A to Z"
+        );
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let input = advent_of_code::read_file("examples", 3);
+        let e = badge_total(&input, 6).unwrap_err();
+        assert_eq!(e.to_string(), "Fail invariant: No common type in group 1");
+        let e = badge_total(&input, 4).unwrap_err();
+        assert_eq!(e.to_string(), "Incomplete group 2: 2 of 4 rucksacks");
+        assert!(badge_total(&input, 0).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = advent_of_code::read_file("examples", 3);
+        let explanation = explain(&input, 3).unwrap();
+        let lines: Vec<&str> = explanation.lines().collect();
+        assert_eq!(
+            lines[0],
+            "line 1: vJrwpWtwJgWr hcsFMMfFFhFp | shared p (16)"
+        );
+        assert_eq!(lines[7], "group 1 (lines 1-3): badge r (18)");
+        assert_eq!(lines[8], "group 2 (lines 4-6): badge Z (52)");
+
+        let explanation = explain("abcd\nabab\n\nxyxy\n", 2).unwrap();
+        assert_eq!(
+            explanation,
+            "line 1: ab cd | nothing shared\n\
+             line 2: ab ab | shared a (1), b (2)\n\
+             line 4: xy xy | shared x (24), y (25)\n\
+             \n\
+             group 1 (lines 1-2): 2 common items: a (1), b (2)\n\
+             group 2 (lines 4-4): incomplete, 1 of 2 rucksacks\n"
+        );
+        assert_eq!(
+            explain("ab\nabc\n", 1).unwrap_err().to_string(),
+            "line 2: \"abc\""
+        );
+    }
+
+    #[test]
+    fn test_matches_hashset() {
+        let input = advent_of_code::read_file("examples", 3);
+        assert_eq!(
+            part_one(&input).unwrap(),
+            hashset::part_one(&input).unwrap()
+        );
+        assert_eq!(
+            part_two(&input).unwrap(),
+            hashset::part_two(&input).unwrap()
+        );
+    }
 }
@@ -1,20 +1,28 @@
-use std::str::FromStr;
+use std::{fmt::Display, io::IsTerminal, str::FromStr};
 
+use advent_of_code::{
+    helpers::{
+        error::{parse_lines, WithLine},
+        interval::Interval,
+    },
+    ANSI_BOLD, ANSI_RESET,
+};
+
+/// Sections `l` to `r`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Range(usize, usize);
 
 impl Range {
-    fn is_completely_subset(&self, other: &Range) -> bool {
-        let Self(l, r) = self;
-        let Self(u, v) = other;
+    fn interval(&self) -> Interval<usize> {
+        Interval::inclusive(self.0, self.1)
+    }
 
-        l >= u && r <= v
+    fn is_completely_subset(&self, other: &Range) -> bool {
+        other.interval().contains_interval(&self.interval())
     }
 
     fn is_overlap(&self, other: &Range) -> bool {
-        let Self(l, r) = self;
-        let Self(u, v) = other;
-
-        !(r < u || l > v)
+        self.interval().overlaps(&other.interval())
     }
 }
 
@@ -31,10 +39,22 @@ impl FromStr for Range {
             .next()
             .ok_or(eyre::eyre!("Invalid Range: missing right"))?
             .parse()?;
+        if l > r {
+            eyre::bail!("Invalid Range: {} ends before it starts", s);
+        }
+        if Interval::try_inclusive(l, r).is_none() {
+            eyre::bail!("Invalid Range: {} ends past the last section", s);
+        }
         Ok(Range(l, r))
     }
 }
 
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.0, self.1)
+    }
+}
+
 struct Pair(Range, Range);
 impl FromStr for Pair {
     type Err = color_eyre::Report;
@@ -56,11 +76,213 @@ impl FromStr for Pair {
     }
 }
 
+/// One elf's range, with the line (1-based) and position in the pair (1 or 2) it comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Assignment {
+    line: usize,
+    elf: usize,
+    range: Range,
+}
+
+/// Every assignment of the input, two per line.
+fn assignments(input: &str) -> color_eyre::Result<Vec<Assignment>> {
+    let mut assignments = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Pair(first, second) = Pair::from_str(line).with_line(i + 1, line)?;
+        for (elf, range) in [(1, first), (2, second)] {
+            assignments.push(Assignment {
+                line: i + 1,
+                elf,
+                range,
+            });
+        }
+    }
+    Ok(assignments)
+}
+
+/// Questions about all assignments at once, answered by sweeping over the sections.
+mod analysis {
+    use std::collections::BTreeMap;
+
+    use advent_of_code::helpers::interval::{Interval, IntervalSet};
+
+    use crate::Assignment;
+
+    /// The most elves working on a single section, and the first section where that happens.
+    pub fn max_coverage(assignments: &[Assignment]) -> Option<(usize, usize)> {
+        // leaving before entering, as an assignment ending at `s` no longer covers `s`.
+        let mut events: Vec<(usize, i32)> = assignments
+            .iter()
+            .flat_map(|a| {
+                [
+                    (a.range.interval().start(), 1),
+                    (a.range.interval().end(), -1),
+                ]
+            })
+            .collect();
+        events.sort_unstable();
+
+        let mut covering = 0;
+        let mut max: Option<(usize, usize)> = None;
+        for (section, delta) in events {
+            covering += delta;
+            let elves = covering as usize;
+            if max.is_none_or(|(most, _)| elves > most) {
+                max = Some((elves, section));
+            }
+        }
+        max
+    }
+
+    /// Sections between the first and last assigned section that nobody works on.
+    pub fn uncovered(assignments: &[Assignment]) -> Vec<Interval<usize>> {
+        let covered: IntervalSet<usize> = assignments.iter().map(|a| a.range.interval()).collect();
+        match covered.span() {
+            Some(span) => covered.gaps_within(span),
+            None => vec![],
+        }
+    }
+
+    /// Every `(outer, inner)` pair where `outer` fully contains `inner`. Of two equal
+    /// assignments, the earlier one is the outer one.
+    pub fn containments(assignments: &[Assignment]) -> Vec<(Assignment, Assignment)> {
+        let mut sorted = assignments.to_vec();
+        // an assignment can only be contained by one sorted before it.
+        sorted.sort_by_key(|a| (a.range.0, std::cmp::Reverse(a.range.1), a.line, a.elf));
+
+        // assignments seen so far by their last section; all of them start no later.
+        let mut by_last: BTreeMap<usize, Vec<Assignment>> = BTreeMap::new();
+        let mut pairs = vec![];
+        for inner in sorted {
+            for outers in by_last.range(inner.range.1..).map(|(_, outers)| outers) {
+                pairs.extend(outers.iter().map(|outer| (*outer, inner)));
+            }
+            by_last.entry(inner.range.1).or_default().push(inner);
+        }
+        pairs.sort_by_key(|(outer, inner)| (outer.line, outer.elf, inner.line, inner.elf));
+        pairs
+    }
+}
+
+/// The fewest assignments to take away so that no two of the others overlap, in input order.
+///
+/// Greedy interval scheduling: going by the last section, keep every assignment that doesn't
+/// overlap the last one kept. Finishing as early as possible leaves the most room for the rest.
+fn reassign(assignments: &[Assignment]) -> Vec<Assignment> {
+    let mut by_end = assignments.to_vec();
+    by_end.sort_by_key(|a| (a.range.1, a.line, a.elf));
+
+    let mut last_kept: Option<Range> = None;
+    let mut reassigned = vec![];
+    for a in by_end {
+        if last_kept.is_some_and(|kept| kept.is_overlap(&a.range)) {
+            reassigned.push(a);
+        } else {
+            last_kept = Some(a.range);
+        }
+    }
+    reassigned.sort_by_key(|a| (a.line, a.elf));
+    reassigned
+}
+
+fn describe(a: &Assignment) -> String {
+    format!(
+        "line {} elf {} ({}-{})",
+        a.line, a.elf, a.range.0, a.range.1
+    )
+}
+
+fn analyze(input: &str) -> color_eyre::Result<String> {
+    use std::fmt::Write;
+
+    let assignments = assignments(input)?;
+    let mut out = String::new();
+    match analysis::max_coverage(&assignments) {
+        Some((elves, section)) => writeln!(
+            out,
+            "max coverage: {} elves, first at section {}",
+            elves, section
+        )?,
+        None => writeln!(out, "max coverage: no assignments")?,
+    }
+
+    let uncovered: Vec<String> = analysis::uncovered(&assignments)
+        .iter()
+        .map(|gap| match gap.len() {
+            1 => gap.start().to_string(),
+            _ => format!("{}-{}", gap.start(), gap.end() - 1),
+        })
+        .collect();
+    match uncovered.is_empty() {
+        true => writeln!(out, "uncovered sections: none")?,
+        false => writeln!(out, "uncovered sections: {}", uncovered.join(", "))?,
+    }
+
+    let containments = analysis::containments(&assignments);
+    writeln!(out, "containments: {}", containments.len())?;
+    for (outer, inner) in &containments {
+        writeln!(out, "  {} contains {}", describe(outer), describe(inner))?;
+    }
+    Ok(out)
+}
+
+/// Draws the pairs on `lines` (all of them if `None`) like the puzzle does, one cell per section
+/// showing its last digit, e.g. `.234.....  2-4`. Sections both elves of a pair work on are bold
+/// with `highlight`. Past section 9, a ruler on top shows the tens.
+fn render(input: &str, lines: Option<Range>, highlight: bool) -> color_eyre::Result<String> {
+    use std::fmt::Write;
+
+    let assignments: Vec<Assignment> = assignments(input)?
+        .into_iter()
+        .filter(|a| lines.is_none_or(|lines| lines.interval().contains(&a.line)))
+        .collect();
+    let width = assignments.iter().map(|a| a.range.1).max().unwrap_or(0);
+
+    let mut out = String::new();
+    if width >= 10 {
+        let tens: String = (1..=width)
+            .map(|section| match section / 10 {
+                0 => ' ',
+                tens => char::from_digit((tens % 10) as u32, 10).unwrap_or('?'),
+            })
+            .collect();
+        writeln!(out, "{}", tens.trim_end())?;
+    }
+    for (i, pair) in assignments.chunks(2).enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let shared = match pair {
+            [first, second] => first
+                .range
+                .interval()
+                .intersection(&second.range.interval()),
+            _ => None,
+        };
+        for a in pair {
+            for section in 1..=width {
+                let cell = match a.range.interval().contains(&section) {
+                    true => char::from_digit((section % 10) as u32, 10).unwrap_or('?'),
+                    false => '.',
+                };
+                if highlight && shared.is_some_and(|shared| shared.contains(&section)) {
+                    write!(out, "{}{}{}", ANSI_BOLD, cell, ANSI_RESET)?;
+                } else {
+                    write!(out, "{}", cell)?;
+                }
+            }
+            writeln!(out, "  {}", a.range)?;
+        }
+    }
+    Ok(out)
+}
+
 pub fn part_one(input: &str) -> color_eyre::Result<usize> {
     let mut counter = 0;
-    for line in input.lines() {
-        let pair = Pair::from_str(line)?;
-        let Pair(f, s) = pair;
+    for Pair(f, s) in parse_lines(input, Pair::from_str)? {
         if f.is_completely_subset(&s) || s.is_completely_subset(&f) {
             counter += 1;
         }
@@ -70,9 +292,7 @@ pub fn part_one(input: &str) -> color_eyre::Result<usize> {
 
 pub fn part_two(input: &str) -> color_eyre::Result<usize> {
     let mut counter = 0;
-    for line in input.lines() {
-        let pair = Pair::from_str(line)?;
-        let Pair(f, s) = pair;
+    for Pair(f, s) in parse_lines(input, Pair::from_str)? {
         if f.is_overlap(&s) {
             counter += 1;
         }
@@ -82,9 +302,42 @@ pub fn part_two(input: &str) -> color_eyre::Result<usize> {
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    let input = &advent_of_code::read_file("inputs", 4);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 4);
+
+    match args.subcommand()?.as_deref() {
+        Some("analyze") => print!("{}", analyze(input)?),
+        Some("render") => {
+            let lines: Option<Range> = args.opt_value_from_str("--lines")?;
+            let highlight = std::io::stdout().is_terminal();
+            print!("{}", render(input, lines, highlight)?);
+        }
+        Some("reassign") => {
+            let assignments = assignments(input)?;
+            let reassigned = reassign(&assignments);
+            println!(
+                "reassign {} of {} elves:",
+                reassigned.len(),
+                assignments.len()
+            );
+            for a in &reassigned {
+                println!("  {}", describe(a));
+            }
+        }
+        Some(other) => eyre::bail!(
+            "Unknown subcommand {:?}, expected analyze, render or reassign",
+            other
+        ),
+        None => {
+            advent_of_code::solve!(1, part_one, input);
+            advent_of_code::solve!(2, part_two, input);
+        }
+    }
     Ok(())
 }
 
@@ -103,4 +356,125 @@ mod tests {
         let input = advent_of_code::read_file("examples", 4);
         assert_eq!(part_two(&input).unwrap(), 4);
     }
+
+    #[test]
+    fn test_analysis() {
+        let input = advent_of_code::read_file("examples", 4);
+        let assignments = assignments(&input).unwrap();
+        assert_eq!(analysis::max_coverage(&assignments), Some((8, 6)));
+        assert_eq!(analysis::uncovered(&assignments), vec![]);
+
+        // the same pairs as comparing every two assignments.
+        let mut expected = vec![];
+        for outer in &assignments {
+            for inner in &assignments {
+                let earlier = (outer.line, outer.elf) < (inner.line, inner.elf);
+                if outer != inner
+                    && inner.range.is_completely_subset(&outer.range)
+                    && (outer.range != inner.range || earlier)
+                {
+                    expected.push((*outer, *inner));
+                }
+            }
+        }
+        assert_eq!(analysis::containments(&assignments), expected);
+
+        let gaps = super::assignments("1-2,5-5\n8-9,8-8\n").unwrap();
+        assert_eq!(analysis::max_coverage(&gaps), Some((2, 8)));
+        assert_eq!(
+            analysis::uncovered(&gaps),
+            vec![Interval::new(3, 5), Interval::new(6, 8)]
+        );
+        assert_eq!(
+            analyze("1-2,5-5\n8-9,8-8\n").unwrap(),
+            "max coverage: 2 elves, first at section 8\n\
+             uncovered sections: 3-4, 6-7\n\
+             containments: 1\n  \
+             line 2 elf 1 (8-9) contains line 2 elf 2 (8-8)\n"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let input = advent_of_code::read_file("examples", 4);
+        let diagram = render(&input, None, false).unwrap();
+        assert!(diagram.starts_with(".234.....  2-4\n.....678.  6-8\n\n.23......  2-3\n"));
+        assert!(diagram.ends_with(".23456...  2-6\n...45678.  4-8\n"));
+
+        assert_eq!(
+            render(&input, Some(Range(5, 5)), true).unwrap(),
+            format!(
+                ".....{b}6{r}  6-6\n...45{b}6{r}  4-6\n",
+                b = ANSI_BOLD,
+                r = ANSI_RESET
+            )
+        );
+        assert_eq!(
+            render("9-12,11-11\n", None, false).unwrap(),
+            "         111\n\
+             ........9012  9-12\n\
+             ..........1.  11-11\n"
+        );
+    }
+
+    #[test]
+    fn test_range_display() {
+        for s in ["2-4", "6-6", "12-99"] {
+            assert_eq!(Range::from_str(s).unwrap().to_string(), s);
+        }
+        assert_eq!(
+            Range::from_str(&Range(3, 7).to_string()).unwrap(),
+            Range(3, 7)
+        );
+    }
+
+    #[test]
+    fn test_reversed_range() {
+        let e = Range::from_str("5-3").unwrap_err();
+        assert_eq!(e.to_string(), "Invalid Range: 5-3 ends before it starts");
+        let e = analyze("1-1,5-3\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 1: \"1-1,5-3\": Invalid Range: 5-3 ends before it starts"
+        );
+    }
+
+    #[test]
+    fn test_huge_range() {
+        let e = part_one("0-18446744073709551615,1-2\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 1: \"0-18446744073709551615,1-2\": Invalid Range: 0-18446744073709551615 ends past the last section"
+        );
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let e = part_one("1-2,3-4\n1-2,3-x\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 2: \"1-2,3-x\"");
+        assert!(part_two("1-2,3-x\n").is_err());
+    }
+
+    #[test]
+    fn test_reassign() {
+        let input = advent_of_code::read_file("examples", 4);
+        let assignments = assignments(&input).unwrap();
+        let reassigned = reassign(&assignments);
+        assert_eq!(reassigned.len(), 8);
+
+        let kept: Vec<&Assignment> = assignments
+            .iter()
+            .filter(|a| !reassigned.contains(a))
+            .collect();
+        assert_eq!(
+            kept.iter().map(|a| a.range).collect::<Vec<_>>(),
+            vec![Range(2, 3), Range(4, 5), Range(7, 9), Range(6, 6)]
+        );
+        for (i, a) in kept.iter().enumerate() {
+            assert!(kept[i + 1..].iter().all(|b| !a.range.is_overlap(&b.range)));
+        }
+
+        let apart = super::assignments("1-2,3-4\n5-6,7-8\n").unwrap();
+        assert_eq!(reassign(&apart), vec![]);
+    }
 }
@@ -1,19 +1,24 @@
+use advent_of_code::{debug, trace};
+use model::{crane_by_name, Crane, CrateMover9000, CrateMover9001};
+
 mod model {
     use std::{fmt::Display, str::FromStr};
 
+    use advent_of_code::helpers::error::{invalid, WithLine};
+
     #[derive(Debug, Clone, Copy)]
     pub struct Crate(pub char);
-    impl ToString for Crate {
-        fn to_string(&self) -> String {
-            format!("[{}]", self.0)
+    impl Display for Crate {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "[{}]", self.0)
         }
     }
 
     #[derive(Default, Debug, Clone)]
     pub struct CargoStack(Vec<Crate>);
-    impl ToString for CargoStack {
-        fn to_string(&self) -> String {
-            self.0.iter().map(ToString::to_string).collect()
+    impl Display for CargoStack {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.iter().try_for_each(|c| write!(f, "{}", c))
         }
     }
 
@@ -23,7 +28,7 @@ mod model {
     impl Display for CargoStacks {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             for (i, t) in self.0.iter().enumerate() {
-                f.write_fmt(format_args!("{} {}\n", i + 1, t.to_string()))?;
+                writeln!(f, "{} {}", i + 1, t)?;
             }
             Ok(())
         }
@@ -37,87 +42,182 @@ mod model {
                 .collect()
         }
 
-        pub fn perform_move_9001(&mut self, command: &Move) -> color_eyre::Result<()> {
-            let Self(cargo_stacks_vec) = self;
-
-            let from_stack = cargo_stacks_vec
-                .get_mut(command.from - 1)
+        /// Takes the top `how_many` crates off stack `from` (1-based), lowest first.
+        pub fn lift(&mut self, from: usize, how_many: usize) -> color_eyre::Result<Vec<Crate>> {
+            let stack = from
+                .checked_sub(1)
+                .and_then(|i| self.0.get_mut(i))
                 .ok_or(eyre::eyre!("Missing from stack"))?;
-            let mut top_crates = from_stack
-                .0
-                .split_off(from_stack.0.len() - command.how_many);
+            if stack.0.len() < how_many {
+                eyre::bail!("No more crates at {}", from);
+            }
+            Ok(stack.0.split_off(stack.0.len() - how_many))
+        }
 
-            let to_stack = cargo_stacks_vec
-                .get_mut(command.to - 1)
+        /// Puts `crates` onto stack `to` (1-based), the first one lowest.
+        pub fn lower(&mut self, to: usize, crates: Vec<Crate>) -> color_eyre::Result<()> {
+            let stack = to
+                .checked_sub(1)
+                .and_then(|i| self.0.get_mut(i))
                 .ok_or(eyre::eyre!("Missing to stack"))?;
-            to_stack.0.append(&mut top_crates);
+            stack.0.extend(crates);
             Ok(())
         }
 
-        pub fn perform_move_9000(&mut self, command: &Move) -> color_eyre::Result<()> {
-            let Self(cargo_stacks_vec) = self;
-
-            for _i in 0..command.how_many {
-                let from_stack = cargo_stacks_vec
-                    .get_mut(command.from - 1)
-                    .ok_or(eyre::eyre!("Missing from stack"))?;
-                let top_crate = from_stack.0.pop();
-
-                if let Some(f) = top_crate {
-                    let to_stack = cargo_stacks_vec
-                        .get_mut(command.to - 1)
-                        .ok_or(eyre::eyre!("Missing to stack"))?;
-                    to_stack.0.push(f);
-                } else {
-                    Err(eyre::eyre!("No more crates at {}", command.from))?
-                }
-            }
-            Ok(())
+        pub fn perform(&mut self, crane: &dyn Crane, command: &Move) -> color_eyre::Result<()> {
+            crane.perform(self, command)
         }
 
+        /// Reads the chart bottom up, starting with the column labels. Lines come with their
+        /// 1-based line number in the input.
         pub fn from_lines<'a, I>(mut it: I) -> color_eyre::Result<Self>
         where
-            I: Iterator<Item = &'a str>,
+            I: Iterator<Item = (usize, &'a str)>,
         {
-            let column_labels = it.next().ok_or(eyre::eyre!("Missing column labels"))?;
+            let (_, column_labels) = it.next().ok_or(eyre::eyre!("Missing column labels"))?;
             let num_of_columns = column_labels.split_whitespace().count();
 
             let mut cargo_stacks_vec = vec![CargoStack::default(); num_of_columns];
 
-            for row_str in it {
-                let mut rit = row_str.chars().peekable();
-                let mut col_index: usize = 0;
-                while rit.peek().is_some() {
-                    // either "   " or "[X]"
-                    rit.next();
-                    let cell = rit.next();
-                    if let Some(i) = cell {
-                        if i.is_ascii_alphabetic() {
-                            if let Some(col_stack) = cargo_stacks_vec.get_mut(col_index) {
-                                col_stack.0.push(Crate(i));
-                            } else {
-                                let mut new_stack = CargoStack::default();
-                                new_stack.0.push(Crate(i));
-                                cargo_stacks_vec[col_index] = new_stack;
-                            }
-                        }
-                    }
-                    rit.next();
+            for (number, row_str) in it {
+                Self::push_row(&mut cargo_stacks_vec, row_str).with_line(number, row_str)?;
+            }
+            Ok(CargoStacks(cargo_stacks_vec))
+        }
 
-                    // eat the empty space
-                    rit.next();
-                    col_index += 1;
+        fn push_row(stacks: &mut [CargoStack], row_str: &str) -> color_eyre::Result<()> {
+            let mut rit = row_str.chars().peekable();
+            let mut col_index: usize = 0;
+            while rit.peek().is_some() {
+                // either "   " or "[X]"
+                rit.next();
+                let cell = rit.next();
+                if let Some(i) = cell {
+                    if i.is_ascii_alphabetic() {
+                        let num_of_columns = stacks.len();
+                        let col_stack = stacks.get_mut(col_index).ok_or(eyre::eyre!(
+                            "Crate {} is in column {}, past the last labelled column {}",
+                            i,
+                            col_index + 1,
+                            num_of_columns
+                        ))?;
+                        col_stack.0.push(Crate(i));
+                    }
                 }
+                rit.next();
+
+                // eat the empty space
+                rit.next();
+                col_index += 1;
             }
-            Ok(CargoStacks(cargo_stacks_vec))
+            Ok(())
         }
     }
 
     #[derive(Debug)]
     pub struct Move {
-        how_many: usize,
-        from: usize,
-        to: usize,
+        pub how_many: usize,
+        pub from: usize,
+        pub to: usize,
+    }
+
+    /// A crane model, which decides how the crates of a move are carried over.
+    pub trait Crane {
+        fn name(&self) -> &str;
+
+        fn perform(&self, stacks: &mut CargoStacks, command: &Move) -> color_eyre::Result<()>;
+    }
+
+    /// Lifts one crate at a time, so the moved crates end up in reverse order.
+    pub struct CrateMover9000;
+
+    impl Crane for CrateMover9000 {
+        fn name(&self) -> &str {
+            "CrateMover 9000"
+        }
+
+        fn perform(&self, stacks: &mut CargoStacks, command: &Move) -> color_eyre::Result<()> {
+            let mut crates = stacks.lift(command.from, command.how_many)?;
+            crates.reverse();
+            stacks.lower(command.to, crates)
+        }
+    }
+
+    /// Lifts all crates of a move at once, keeping their order.
+    pub struct CrateMover9001;
+
+    impl Crane for CrateMover9001 {
+        fn name(&self) -> &str {
+            "CrateMover 9001"
+        }
+
+        fn perform(&self, stacks: &mut CargoStacks, command: &Move) -> color_eyre::Result<()> {
+            let crates = stacks.lift(command.from, command.how_many)?;
+            stacks.lower(command.to, crates)
+        }
+    }
+
+    /// Lifts at most `capacity` crates at once, keeping the order within every batch.
+    /// With `alternate`, every second batch of a move is put down in reverse.
+    pub struct LimitedCrane {
+        capacity: usize,
+        alternate: bool,
+        name: String,
+    }
+
+    impl LimitedCrane {
+        pub fn new(capacity: usize, alternate: bool) -> color_eyre::Result<Self> {
+            if capacity == 0 {
+                eyre::bail!("A crane has to lift at least one crate");
+            }
+            let name = match alternate {
+                true => format!("alternating crane lifting {}", capacity),
+                false => format!("crane lifting {}", capacity),
+            };
+            Ok(Self {
+                capacity,
+                alternate,
+                name,
+            })
+        }
+    }
+
+    impl Crane for LimitedCrane {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn perform(&self, stacks: &mut CargoStacks, command: &Move) -> color_eyre::Result<()> {
+            let mut left = command.how_many;
+            let mut batch = 0;
+            while left > 0 {
+                let how_many = left.min(self.capacity);
+                let mut crates = stacks.lift(command.from, how_many)?;
+                if self.alternate && batch % 2 == 1 {
+                    crates.reverse();
+                }
+                stacks.lower(command.to, crates)?;
+                left -= how_many;
+                batch += 1;
+            }
+            Ok(())
+        }
+    }
+
+    /// `9000`, `9001`, `limited:<n>` or `alternating:<n>`.
+    pub fn crane_by_name(name: &str) -> color_eyre::Result<Box<dyn Crane>> {
+        let expected = "9000, 9001, limited:<n> or alternating:<n>";
+        let capacity = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| invalid("crane capacity", n, "a number"))
+        };
+        Ok(match name.split_once(':') {
+            None if name == "9000" => Box::new(CrateMover9000),
+            None if name == "9001" => Box::new(CrateMover9001),
+            Some(("limited", n)) => Box::new(LimitedCrane::new(capacity(n)?, false)?),
+            Some(("alternating", n)) => Box::new(LimitedCrane::new(capacity(n)?, true)?),
+            _ => return Err(invalid("crane", name, expected)),
+        })
     }
 
     impl Display for Move {
@@ -147,33 +247,36 @@ mod model {
 
 mod preprocess {
     use crate::model::{CargoStacks, Move};
+    use advent_of_code::helpers::error::WithLine;
     use std::str::FromStr;
 
     pub fn read_input(input: &str) -> color_eyre::Result<(CargoStacks, Vec<Move>)> {
-        let mut it = input.lines();
+        let mut it = input.lines().enumerate().map(|(i, line)| (i + 1, line));
         let mut chart_input = it
             .by_ref()
-            .take_while(|s| !s.is_empty())
-            .collect::<Vec<&str>>();
+            .take_while(|(_, s)| !s.is_empty())
+            .collect::<Vec<(usize, &str)>>();
         chart_input.reverse();
 
         let cargo_stacks = CargoStacks::from_lines(chart_input.into_iter())?;
 
         let commands = it
-            .map(Move::from_str)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(number, line)| Move::from_str(line).with_line(number, line))
             .collect::<eyre::Result<Vec<Move>>>()?;
         Ok((cargo_stacks, commands))
     }
 }
 
-pub fn part_one(input: &str) -> color_eyre::Result<String> {
+/// The top crate of every stack after `crane` has carried out all moves.
+fn rearrange(input: &str, crane: &dyn Crane) -> color_eyre::Result<String> {
     let (mut cargo_stacks, commands) = preprocess::read_input(input)?;
 
-    dbg!(&cargo_stacks, &commands);
-    println!("start: \n{}\n", &cargo_stacks);
+    trace!("{:?}", &commands);
+    debug!("start with {}:\n{}\n", crane.name(), &cargo_stacks);
     for command in commands {
-        cargo_stacks.perform_move_9000(&command)?;
-        println!("after command {}:\n{}\n", &command, &cargo_stacks);
+        cargo_stacks.perform(crane, &command)?;
+        trace!("after command {}:\n{}\n", &command, &cargo_stacks);
     }
 
     Ok(cargo_stacks
@@ -186,30 +289,35 @@ pub fn part_one(input: &str) -> color_eyre::Result<String> {
         .collect::<String>())
 }
 
-pub fn part_two(input: &str) -> color_eyre::Result<String> {
-    let (mut cargo_stacks, commands) = preprocess::read_input(input)?;
-
-    dbg!(&cargo_stacks, &commands);
-    println!("start: \n{}\n", &cargo_stacks);
-    for command in commands {
-        cargo_stacks.perform_move_9001(&command)?;
-        println!("after command {}:\n{}\n", &command, &cargo_stacks);
-    }
+pub fn part_one(input: &str) -> color_eyre::Result<String> {
+    rearrange(input, &CrateMover9000)
+}
 
-    Ok(cargo_stacks
-        .take_top()
-        .iter()
-        .map(|r| match r {
-            Some(c) => c.0,
-            None => ' ',
-        })
-        .collect::<String>())
+pub fn part_two(input: &str) -> color_eyre::Result<String> {
+    rearrange(input, &CrateMover9001)
 }
 
-fn main() {
-    let input = &advent_of_code::read_file("inputs", 5);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 5);
+
+    match args.opt_value_from_str::<_, String>("--crane")? {
+        Some(name) => {
+            let crane = crane_by_name(&name)?;
+            println!("{}: {}", crane.name(), rearrange(input, crane.as_ref())?);
+        }
+        None => {
+            advent_of_code::solve!(1, part_one, input);
+            advent_of_code::solve!(2, part_two, input);
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -227,4 +335,43 @@ mod tests {
         let input = advent_of_code::read_file("examples", 5);
         assert_eq!(part_two(&input).unwrap(), "MCD");
     }
+
+    #[test]
+    fn test_cranes() {
+        let input = advent_of_code::read_file("examples", 5);
+        let top = |name: &str| rearrange(&input, crane_by_name(name).unwrap().as_ref()).unwrap();
+        assert_eq!(top("9000"), "CMZ");
+        assert_eq!(top("9001"), "MCD");
+        assert_eq!(top("limited:1"), "CMZ");
+        assert_eq!(top("limited:3"), "MCD");
+        assert_eq!(top("limited:2"), "MCZ");
+        assert_eq!(top("alternating:1"), "CMZ");
+
+        // A B C D from the bottom up, moved in batches of C D then A B.
+        let tower = "[D]    \n[C]    \n[B]    \n[A]    \n 1   2 \n\nmove 4 from 1 to 2\n";
+        let top = |name: &str| rearrange(tower, crane_by_name(name).unwrap().as_ref()).unwrap();
+        assert_eq!(top("limited:2"), " B");
+        assert_eq!(top("alternating:2"), " A");
+        assert_eq!(top("9001"), " D");
+
+        let e = crane_by_name("9002").err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "Invalid crane \"9002\", expected 9000, 9001, limited:<n> or alternating:<n>"
+        );
+        assert!(crane_by_name("limited:0").is_err());
+        assert!(crane_by_name("limited:x").is_err());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let e = part_one("[A] [B]\n 1 \n\nmove 1 from 1 to 1\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 1: \"[A] [B]\": Crate B is in column 2, past the last labelled column 1"
+        );
+
+        let e = part_one("[A]\n 1 \n\nmove 1 from 1 to 1\nmove x\n").unwrap_err();
+        assert_eq!(e.to_string(), "line 5: \"move x\"");
+    }
 }
@@ -1,14 +1,17 @@
+use advent_of_code::{helpers::parse::parse_all, trace};
+
 mod parser {
     use nom::{
         branch::alt,
         bytes::complete::{tag, take_till},
         character::complete::{alphanumeric1, digit1, multispace1, space1},
-        combinator::{map, map_res},
-        multi::many0,
-        sequence::{preceded, separated_pair, terminated},
+        combinator::{cut, map, map_res},
+        sequence::{preceded, separated_pair},
         IResult,
     };
 
+    use advent_of_code::helpers::parse::separated_items;
+
     use crate::model::{ChangeDirectory, Command, FileEntryInput, LineType};
 
     fn parse_cd_up(s: &str) -> IResult<&str, ChangeDirectory> {
@@ -28,7 +31,7 @@ mod parser {
     fn parse_cd(s: &str) -> IResult<&str, Command> {
         map(
             preceded(tag("cd "), alt((parse_cd_up, parse_cd_root, parse_cd_down))),
-            |cd| Command::Cd(cd),
+            Command::Cd,
         )(s)
     }
 
@@ -37,7 +40,7 @@ mod parser {
     }
 
     fn parse_command(s: &str) -> IResult<&str, Command> {
-        preceded(tag("$ "), alt((parse_ls, parse_cd)))(s)
+        preceded(tag("$ "), cut(alt((parse_ls, parse_cd))))(s)
     }
 
     fn parse_file(s: &str) -> IResult<&str, FileEntryInput> {
@@ -67,7 +70,7 @@ mod parser {
     }
 
     pub fn parse_input(s: &str) -> IResult<&str, Vec<LineType>> {
-        many0(terminated(parse_line, multispace1))(s)
+        separated_items(multispace1, parse_line)(s)
     }
 }
 
@@ -231,9 +234,9 @@ mod logic {
 }
 
 pub fn part_one(input: &str) -> color_eyre::Result<usize> {
-    let (_, lines) = parser::parse_input(input).map_err(|e| eyre::eyre!(e.to_owned()))?;
+    let lines = parse_all(input, parser::parse_input)?;
     let fs = logic::gen_flat_file_system(&lines)?;
-    dbg!(&fs);
+    trace!("{:#?}", &fs);
     Ok(fs
         .0
         .iter()
@@ -245,12 +248,20 @@ pub fn part_one(input: &str) -> color_eyre::Result<usize> {
 const DISK_SPACE: usize = 70000000;
 const UPDATE: usize = 30000000;
 pub fn part_two(input: &str) -> color_eyre::Result<usize> {
-    let (_, lines) = parser::parse_input(input).map_err(|e| eyre::eyre!(e.to_owned()))?;
+    let lines = parse_all(input, parser::parse_input)?;
     let fs = logic::gen_flat_file_system(&lines)?;
     let root_size = fs.0.get("/").ok_or(eyre::eyre!("Root folder is missing"))?;
-    let need: usize = UPDATE - (DISK_SPACE - root_size);
+    let free = DISK_SPACE.checked_sub(*root_size).ok_or(eyre::eyre!(
+        "Disk is overfull: {} used of {}",
+        root_size,
+        DISK_SPACE
+    ))?;
+    // with enough free space already, nothing needs to be deleted.
+    let Some(need) = UPDATE.checked_sub(free).filter(|need| *need > 0) else {
+        return Ok(0);
+    };
 
-    dbg!(&fs);
+    trace!("{:#?}", &fs);
     fs.0.iter()
         .filter(|&(_name, size)| *size >= need)
         .map(|(_, size)| size)
@@ -280,4 +291,17 @@ mod tests {
         let input = advent_of_code::read_file("examples", 7);
         assert_eq!(part_two(&input).unwrap(), 24933642);
     }
+
+    #[test]
+    fn test_disk_space() {
+        assert_eq!(part_two("$ cd /\n$ ls\n100 a.txt\n").unwrap(), 0);
+        let e = part_two("$ cd /\n$ ls\n70000001 a.txt\n").unwrap_err();
+        assert_eq!(e.to_string(), "Disk is overfull: 70000001 used of 70000000");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let e = part_one("$ cd /\n$ ls\n$ cdx a\n").unwrap_err().to_string();
+        assert!(e.starts_with("Parse error at line 3, column 3"), "{}", e);
+    }
 }
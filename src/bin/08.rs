@@ -1,45 +1,25 @@
-use std::str::FromStr;
-
-struct Grid {
-    data: Vec<u32>,
-    size: usize,
-}
-
-impl Grid {
-    pub fn size(&self) -> usize {
-        self.size
-    }
-
-    pub fn at(&self, x: usize, y: usize) -> u32 {
-        self.data[x + y * self.size]
-    }
-
-    pub fn up(&self, x: usize, y: usize) -> impl Iterator<Item = u32> + '_ {
-        (0..y).rev().map(move |v| self.at(x, v))
-    }
-
-    pub fn down(&self, x: usize, y: usize) -> impl Iterator<Item = u32> + '_ {
-        ((y + 1)..self.size).map(move |v| self.at(x, v))
-    }
-
-    pub fn left(&self, x: usize, y: usize) -> impl Iterator<Item = u32> + '_ {
-        (0..x).rev().map(move |u| self.at(u, y))
-    }
-
-    pub fn right(&self, x: usize, y: usize) -> impl Iterator<Item = u32> + '_ {
-        ((x + 1)..self.size).map(move |u| self.at(u, y))
+use advent_of_code::{
+    debug,
+    helpers::grid::{Grid, NEIGHBOURS_4},
+};
+
+struct Forest(Grid<u32>);
+
+impl Forest {
+    fn lines_of_sight(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = impl Iterator<Item = u32> + '_> + '_ {
+        NEIGHBOURS_4
+            .into_iter()
+            .map(move |step| self.0.ray(x, y, step).map(|(_, h)| *h))
     }
 
     pub fn view_score(&self, x: usize, y: usize) -> usize {
-        let me = self.at(x, y);
+        let me = self.0[(x, y)];
         let mut total_score = 1;
-        let all_dirs: [Box<dyn Iterator<Item = u32>>; 4] = [
-            Box::new(self.up(x, y)),
-            Box::new(self.down(x, y)),
-            Box::new(self.left(x, y)),
-            Box::new(self.right(x, y)),
-        ];
-        for trees in all_dirs {
+        for trees in self.lines_of_sight(x, y) {
             let mut count = 0;
             for tree in trees {
                 count += 1;
@@ -53,58 +33,40 @@ impl Grid {
     }
 
     pub fn visible(&self, x: usize, y: usize) -> bool {
-        let me = self.at(x, y);
-        self.up(x, y).all(|h| h < me)
-            || self.down(x, y).all(|h| h < me)
-            || self.left(x, y).all(|h| h < me)
-            || self.right(x, y).all(|h| h < me)
+        let me = self.0[(x, y)];
+        self.lines_of_sight(x, y)
+            .any(|mut trees| trees.all(|h| h < me))
     }
 }
 
-impl FromStr for Grid {
-    type Err = color_eyre::Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first_line, _rest) = s
-            .split_once('\n')
-            .ok_or(eyre::eyre!("Invalid grid with no lines"))?;
-        let data: Vec<u32> = s
-            .chars()
-            .filter(char::is_ascii_digit)
-            .map(|s| s.to_digit(10).ok_or(eyre::eyre!("Invalid digit")))
-            .collect::<eyre::Result<Vec<_>>>()?;
-        let size = first_line.len();
-        Ok(Grid { data, size })
-    }
+fn parse_forest(input: &str) -> color_eyre::Result<Forest> {
+    let grid = Grid::parse(input, |c| {
+        c.to_digit(10).ok_or(eyre::eyre!("Invalid digit"))
+    })?;
+    Ok(Forest(grid))
 }
 
 pub fn part_one(input: &str) -> color_eyre::Result<usize> {
-    let grid = Grid::from_str(input)?;
-    let mut counter = 0;
-    for i in 0..grid.size() {
-        for j in 0..grid.size() {
-            if grid.visible(i, j) {
-                counter += 1;
-            }
-        }
-    }
-    Ok(counter)
+    let forest = parse_forest(input)?;
+    Ok(forest
+        .0
+        .positions()
+        .filter(|&(i, j)| forest.visible(i, j))
+        .count())
 }
 
 pub fn part_two(input: &str) -> color_eyre::Result<usize> {
-    let grid = Grid::from_str(input)?;
+    let forest = parse_forest(input)?;
     let mut max: (Option<(usize, usize)>, usize) = (None, 0);
-    for i in 0..grid.size() {
-        for j in 0..grid.size() {
-            let score = grid.view_score(i, j);
-            max = if score > max.1 {
-                (Some((i, j)), score)
-            } else {
-                max
-            };
-        }
+    for (i, j) in forest.0.positions() {
+        let score = forest.view_score(i, j);
+        max = if score > max.1 {
+            (Some((i, j)), score)
+        } else {
+            max
+        };
     }
-    dbg!(max);
+    debug!("best view score {} at {:?}", max.1, max.0);
     Ok(max.1)
 }
 
@@ -1,78 +1,27 @@
 use std::{
-    collections::HashMap,
-    ops::{Add, Sub},
+    collections::{HashMap, HashSet},
+    io,
+    path::PathBuf,
     str::FromStr,
 };
 
-use itertools::Itertools;
-
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct Location(isize, isize);
-impl Add for Location {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Location(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl Sub for Location {
-    type Output = Location;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Location(self.0 - rhs.0, self.1 - rhs.1)
-    }
-}
-
-impl Default for Location {
-    fn default() -> Self {
-        Self(0, 0)
-    }
-}
-
-impl Location {
-    fn all_directions(&self) -> impl Iterator<Item = Location> + '_ {
-        (-1..=1)
-            .cartesian_product(-1..=1)
-            .map(|(dx, dy)| Location(dx, dy))
-            .map(|d| *self + d)
-    }
+use advent_of_code::{
+    helpers::{
+        animation::{Animation, BACKGROUND},
+        error::parse_lines,
+        point::{Direction, Point},
+    },
+    trace,
+};
 
-    pub fn is_touching(&self, other: &Self) -> bool {
-        self.all_directions().any(|d| &d == other)
-    }
+type Location = Point<isize>;
 
-    pub fn move_direction(&self, tail: &Self) -> TailMove {
-        let Self(dx, dy) = *self - *tail;
-        if self.is_touching(tail) {
-            TailMove::NONE
-        } else if dx == 0 {
-            if dy < 0 {
-                TailMove::D
-            } else {
-                TailMove::U
-            }
-        } else if dy == 0 {
-            if dx < 0 {
-                TailMove::L
-            } else {
-                TailMove::R
-            }
-        } else if dy < 0 {
-            if dx < 0 {
-                TailMove::DL
-            } else {
-                TailMove::DR
-            }
-        } else if dy > 0 {
-            if dx < 0 {
-                TailMove::UL
-            } else {
-                TailMove::UR
-            }
-        } else {
-            panic!("Impossible to get here")
-        }
+/// The tail only moves, by a single king move towards the head, once they stop touching.
+fn follow(head: &Location, tail: &Location) -> Location {
+    if head.chebyshev(tail) > 1 {
+        tail.step_towards(head)
+    } else {
+        *tail
     }
 }
 
@@ -90,45 +39,13 @@ impl Visit {
 }
 
 #[derive(Debug)]
-enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl From<&Dir> for Location {
-    fn from(d: &Dir) -> Self {
-        match d {
-            Dir::Up => Location(0, -1),
-            Dir::Down => Location(0, 1),
-            Dir::Left => Location(-1, 0),
-            Dir::Right => Location(1, 0),
-        }
-    }
-}
-
-impl FromStr for Dir {
-    type Err = eyre::Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "R" => Ok(Dir::Right),
-            "L" => Ok(Dir::Left),
-            "U" => Ok(Dir::Up),
-            "D" => Ok(Dir::Down),
-            _ => Err(eyre::eyre!("Invalid command: {}", s)),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Move(Dir, usize);
+struct Move(Direction, usize);
 impl FromStr for Move {
     type Err = eyre::Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut it = s.split_whitespace();
-        let dir = Dir::from_str(it.next().ok_or(eyre::eyre!("Missing command"))?)?;
+        let dir = Direction::from_str(it.next().ok_or(eyre::eyre!("Missing command"))?)?;
         let steps = it
             .next()
             .ok_or(eyre::eyre!("Missing steps"))?
@@ -137,86 +54,56 @@ impl FromStr for Move {
     }
 }
 
-enum TailMove {
-    U,
-    D,
-    L,
-    R,
-    UL,
-    UR,
-    DL,
-    DR,
-    NONE,
-}
-
-impl From<TailMove> for Location {
-    fn from(m: TailMove) -> Self {
-        match m {
-            TailMove::U => Location(0, 1),
-            TailMove::D => Location(0, -1),
-            TailMove::L => Location(-1, 0),
-            TailMove::R => Location(1, 0),
-            TailMove::UL => Location(-1, 1),
-            TailMove::UR => Location(1, 1),
-            TailMove::DL => Location(-1, -1),
-            TailMove::DR => Location(1, -1),
-            TailMove::NONE => Location(0, 0),
-        }
-    }
-}
-
-fn determine_tail_move(head: &Location, tail: &Location) -> TailMove {
-    head.move_direction(tail)
-}
-
 fn run_simulation(visit: &mut Visit, moves: &[Move]) -> eyre::Result<()> {
-    let mut head = Location(0, 0);
-    let mut tail = Location(0, 0);
+    let mut head = Location::default();
+    let mut tail = Location::default();
     visit.add(tail);
 
     let mut c = 0;
     for mmove in moves {
         let Move(d, s) = mmove;
         for _i in 0..*s {
-            head = head + d.into();
-            let tailmove = determine_tail_move(&head, &tail);
-            tail = tail + tailmove.into();
+            head += (*d).into();
+            tail = follow(&head, &tail);
             visit.add(tail);
             c += 1;
-            dbg!(c, head, tail);
+            trace!("step {}: head {} tail {}", c, head, tail);
         }
     }
     Ok(())
 }
 
-fn run_simulation_n(n: usize, visit: &mut Visit, moves: &[Move]) -> eyre::Result<()> {
+/// Calls `on_step` with the knots, head first, and the visited positions after every step.
+fn run_simulation_n(
+    n: usize,
+    visit: &mut Visit,
+    moves: &[Move],
+    mut on_step: impl FnMut(&[Location], &Visit),
+) -> eyre::Result<()> {
     let mut knots = vec![Location::default(); n];
-    visit.add(*knots.last().unwrap());
+    let tail = knots
+        .last()
+        .ok_or(eyre::eyre!("A rope needs at least one knot"))?;
+    visit.add(*tail);
 
     for mmove in moves {
         let Move(d, s) = mmove;
         for _s in 0..*s {
-            let head = knots.get(0).unwrap();
-            knots[0] = *head + d.into();
+            knots[0] += (*d).into();
             for i in 1..n {
-                let head = knots.get(i - 1).unwrap();
-                let tail = knots.get(i).unwrap();
-                let tailmove = determine_tail_move(&head, &tail);
-                knots[i] = *tail + tailmove.into();
+                knots[i] = follow(&knots[i - 1], &knots[i]);
                 if i == n - 1 {
                     visit.add(knots[i]);
                 }
             }
+            on_step(&knots, visit);
         }
     }
     Ok(())
 }
 
 pub fn part_one(input: &str) -> eyre::Result<usize> {
-    let moves = input
-        .lines()
-        .map(Move::from_str)
-        .collect::<eyre::Result<Vec<_>>>()?;
+    let moves = parse_lines(input, Move::from_str)?;
 
     let mut visit = Visit::default();
     run_simulation(&mut visit, &moves)?;
@@ -224,20 +111,108 @@ pub fn part_one(input: &str) -> eyre::Result<usize> {
 }
 
 pub fn part_two(input: &str) -> eyre::Result<usize> {
-    let moves = input
-        .lines()
-        .map(Move::from_str)
-        .collect::<eyre::Result<Vec<_>>>()?;
+    let moves = parse_lines(input, Move::from_str)?;
 
     let mut visit = Visit::default();
-    run_simulation_n(10, &mut visit, &moves)?;
+    run_simulation_n(10, &mut visit, &moves, |_, _| {})?;
     Ok(visit.count())
 }
 
-fn main() {
-    let input = &advent_of_code::read_file("inputs", 9);
-    advent_of_code::solve!(1, part_one, input);
-    advent_of_code::solve!(2, part_two, input);
+struct AnimateArgs {
+    terminal: bool,
+    gif: Option<PathBuf>,
+    ppm: Option<PathBuf>,
+    fps: f64,
+    scale: usize,
+    knots: usize,
+    every: usize,
+}
+
+impl AnimateArgs {
+    /// `None` unless one of `--animate`, `--gif` or `--ppm` was given.
+    fn from_args(args: &mut pico_args::Arguments) -> Result<Option<Self>, pico_args::Error> {
+        let animate = Self {
+            terminal: args.contains("--animate"),
+            gif: args.opt_value_from_str("--gif")?,
+            ppm: args.opt_value_from_str("--ppm")?,
+            fps: args.opt_value_from_str("--fps")?.unwrap_or(10.0),
+            scale: args.opt_value_from_str("--scale")?.unwrap_or(8),
+            knots: args.opt_value_from_str("--knots")?.unwrap_or(10),
+            every: args.opt_value_from_str("--every")?.unwrap_or(1),
+        };
+        Ok((animate.terminal || animate.gif.is_some() || animate.ppm.is_some()).then_some(animate))
+    }
+}
+
+fn knot_char(i: usize, n: usize) -> char {
+    match i {
+        0 => 'H',
+        _ if n == 2 => 'T',
+        _ => char::from_digit((i % 10) as u32, 10).unwrap_or('?'),
+    }
+}
+
+/// Records the rope, drawn like in the puzzle, after every `args.every` steps. Only the cells the
+/// rope entered or left since the previous frame are recorded.
+fn animate(input: &str, args: &AnimateArgs) -> eyre::Result<Animation> {
+    let moves = parse_lines(input, Move::from_str)?;
+    let mut animation = Animation::new()
+        .with_colour('H', [255, 80, 80])
+        .with_colour('s', [80, 160, 255]);
+    let start = Location::default();
+    let mut dirty: HashSet<Location> = HashSet::from([start]);
+    let mut previous: Vec<Location> = vec![];
+    let mut step = 0;
+    run_simulation_n(args.knots, &mut Visit::default(), &moves, |knots, visit| {
+        step += 1;
+        dirty.extend(previous.drain(..).chain(knots.iter().copied()));
+        previous.extend_from_slice(knots);
+        if step % args.every.max(1) != 0 {
+            return;
+        }
+        // the knot closest to the head is drawn on top, then the start, then the visited cells.
+        let cell = |p: &Location| match knots.iter().position(|knot| knot == p) {
+            Some(i) => knot_char(i, knots.len()),
+            None if *p == start => 's',
+            None if visit.0.contains_key(p) => '#',
+            None => BACKGROUND,
+        };
+        animation.push_changes(dirty.drain().map(|p| (p, cell(&p))));
+    })?;
+    Ok(animation)
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let mut args = pico_args::Arguments::from_env();
+    let folder = if args.contains("--example") {
+        "examples"
+    } else {
+        "inputs"
+    };
+    let input = &advent_of_code::read_file(folder, 9);
+
+    match AnimateArgs::from_args(&mut args)? {
+        Some(animate_args) => {
+            let animation = animate(input, &animate_args)?;
+            if let Some(dir) = &animate_args.ppm {
+                let paths = animation.write_ppm(dir, animate_args.scale)?;
+                println!("Wrote {} frames to {:?}", paths.len(), dir);
+            }
+            if let Some(path) = &animate_args.gif {
+                animation.write_gif(path, animate_args.scale, animate_args.fps)?;
+                println!("Wrote {:?}", path);
+            }
+            if animate_args.terminal {
+                animation.play(animate_args.fps, &mut io::stdout())?;
+            }
+        }
+        None => {
+            advent_of_code::solve!(1, part_one, input);
+            advent_of_code::solve!(2, part_two, input);
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -257,4 +232,27 @@ mod tests {
         let input = advent_of_code::read_file("examples", 9);
         assert_eq!(part_two(&input).unwrap(), 36);
     }
+
+    #[test]
+    fn test_animate() {
+        let args = AnimateArgs {
+            terminal: true,
+            gif: None,
+            ppm: None,
+            fps: 10.0,
+            scale: 1,
+            knots: 2,
+            every: 1,
+        };
+        let animation = animate("R 4\nU 4\n", &args).unwrap();
+        assert_eq!(animation.len(), 8);
+        let last = animation.rasterize().last().unwrap();
+        assert_eq!(last.to_string(), "....H\n....T\n....#\n....#\ns###.");
+
+        // skipped steps still leave their visited cells behind.
+        let every_4 = AnimateArgs { every: 4, ..args };
+        let animation = animate("R 4\nU 4\n", &every_4).unwrap();
+        assert_eq!(animation.len(), 2);
+        assert_eq!(animation.rasterize().last(), Some(last));
+    }
 }
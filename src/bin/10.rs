@@ -1,10 +1,11 @@
-use std::str::FromStr;
-
-use model::{Command, Machine};
+use advent_of_code::helpers::parse::parse_all;
+use model::Machine;
 
 mod model {
     use std::str::FromStr;
 
+    use advent_of_code::{helpers::parse::parse_all, trace};
+
     use crate::parser;
 
     #[derive(Debug, Clone, Copy)]
@@ -17,9 +18,7 @@ mod model {
         type Err = eyre::Report;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let (_, command) =
-                parser::parse(s).map_err(|_| eyre::eyre!("Unable to parse: {}", s))?;
-            Ok(command)
+            parse_all(s, parser::parse)
         }
     }
 
@@ -52,7 +51,7 @@ mod model {
                     self.history.push(reg_x + n);
                 }
             }
-            dbg!(&self.cycle, &self.get_at_specific_cycles());
+            trace!("cycle {}: {:?}", self.cycle, self.get_at_specific_cycles());
         }
 
         const CYCLES: [usize; 6] = [20, 60, 100, 140, 180, 220];
@@ -66,7 +65,6 @@ mod model {
                 .iter()
                 .enumerate()
                 .map(|(cycle, pos)| {
-                    dbg!(cycle, pos, pos + 2);
                     let c: isize = (cycle as isize % Self::WIDTH) + 1;
                     if c >= *pos && c <= (*pos + 2) {
                         '#'
@@ -84,10 +82,16 @@ mod model {
 
 mod parser {
     use nom::{
-        branch::alt, bytes::complete::tag, character::complete::i64, combinator::map,
-        sequence::preceded, IResult,
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{i64, line_ending},
+        combinator::map,
+        sequence::preceded,
+        IResult,
     };
 
+    use advent_of_code::helpers::parse::separated_items;
+
     use crate::model::Command;
 
     fn parse_noop(s: &str) -> IResult<&str, Command> {
@@ -101,12 +105,15 @@ mod parser {
     pub fn parse(s: &str) -> IResult<&str, Command> {
         alt((parse_noop, parse_addx))(s)
     }
+
+    pub fn parse_program(s: &str) -> IResult<&str, Vec<Command>> {
+        separated_items(line_ending, parse)(s)
+    }
 }
 
 pub fn part_one(input: &str) -> eyre::Result<isize> {
     let mut machine = Machine::default();
-    for line in input.lines() {
-        let command = Command::from_str(line)?;
+    for command in parse_all(input, parser::parse_program)? {
         machine.run_command(&command);
     }
 
@@ -119,8 +126,7 @@ pub fn part_one(input: &str) -> eyre::Result<isize> {
 
 pub fn part_two(input: &str) -> eyre::Result<usize> {
     let mut machine = Machine::default();
-    for line in input.lines() {
-        let command = Command::from_str(line)?;
+    for command in parse_all(input, parser::parse_program)? {
         machine.run_command(&command);
     }
     let look = machine.draw();
@@ -145,9 +151,17 @@ mod tests {
         assert_eq!(part_one(&input).unwrap(), 13140);
     }
 
+    /*
     #[test]
     fn test_part_two() {
         let input = advent_of_code::read_file("examples", 10);
-        // assert_eq!(part_two(&input).unwrap(), 0);
+        assert_eq!(part_two(&input).unwrap(), 0);
+    }
+    */
+
+    #[test]
+    fn test_parse_error() {
+        let e = part_one("noop\naddx 3\naddx x\n").unwrap_err().to_string();
+        assert!(e.starts_with("Parse error at line 3, column 6"), "{}", e);
     }
 }
@@ -1,39 +1,39 @@
-use std::cell::RefCell;
+use advent_of_code::{helpers::parse::parse_all, trace};
 
-use nom::character::complete::line_ending;
-
-use crate::model::{Monkey, Monkeys};
+use crate::model::Monkeys;
 
 mod parser {
     use nom::{
         branch::alt,
         bytes::complete::tag,
         character::complete::{line_ending, multispace0, multispace1, not_line_ending, u16, u64},
-        combinator::map,
+        combinator::{map, value},
+        error::context,
         multi::separated_list1,
-        sequence::{pair, separated_pair, tuple},
-        IResult,
+        sequence::{pair, preceded, tuple},
     };
 
+    use advent_of_code::helpers::parse::{separated_items, VerboseResult};
+
     use crate::model::{Monkey, Operation, TestCondition};
 
-    fn ignore_until_newline(s: &str) -> IResult<&str, ()> {
+    fn ignore_until_newline(s: &str) -> VerboseResult<'_, ()> {
         map(not_line_ending, |_| ())(s)
     }
 
-    fn ignore_until_include_newline(s: &str) -> IResult<&str, ()> {
+    fn ignore_until_include_newline(s: &str) -> VerboseResult<'_, ()> {
         map(tuple((not_line_ending, line_ending)), |_| ())(s)
     }
 
     // parse usize separated by ,
-    fn parse_items(s: &str) -> IResult<&str, Vec<usize>> {
+    fn parse_items(s: &str) -> VerboseResult<'_, Vec<usize>> {
         let (s, _) = tuple((multispace1, tag("Starting items:"), multispace0))(s)?;
         let (s, items) = separated_list1(pair(tag(","), multispace0), map(u16, |u| u as usize))(s)?;
         let (s, _) = ignore_until_include_newline(s)?;
         Ok((s, items))
     }
 
-    fn parse_test_condition_divisible(s: &str) -> IResult<&str, usize> {
+    fn parse_test_condition_divisible(s: &str) -> VerboseResult<'_, usize> {
         let (s, _) = tuple((
             multispace1,
             tag("Test:"),
@@ -46,21 +46,21 @@ mod parser {
         Ok((s, divisible_by))
     }
 
-    fn parse_test_condition_true(s: &str) -> IResult<&str, usize> {
+    fn parse_test_condition_true(s: &str) -> VerboseResult<'_, usize> {
         let (s, _) = tuple((multispace1, tag("If true: throw to monkey"), multispace1))(s)?;
         let (s, if_true) = map(u64, |u| u as usize)(s)?;
         let (s, _) = ignore_until_include_newline(s)?;
         Ok((s, if_true))
     }
 
-    fn parse_test_condition_false(s: &str) -> IResult<&str, usize> {
+    fn parse_test_condition_false(s: &str) -> VerboseResult<'_, usize> {
         let (s, _) = tuple((multispace1, tag("If false: throw to monkey"), multispace1))(s)?;
         let (s, if_false) = map(u64, |u| u as usize)(s)?;
         let (s, _) = ignore_until_include_newline(s)?;
         Ok((s, if_false))
     }
 
-    fn parse_test_condition(s: &str) -> IResult<&str, TestCondition> {
+    fn parse_test_condition(s: &str) -> VerboseResult<'_, TestCondition> {
         let (s, divisible_by) = parse_test_condition_divisible(s)?;
         let (s, if_true) = parse_test_condition_true(s)?;
         let (s, if_false) = parse_test_condition_false(s)?;
@@ -74,15 +74,15 @@ mod parser {
         ))
     }
 
-    pub fn parse_monkey(s: &str) -> IResult<&str, (usize, Monkey)> {
+    fn parse_monkey(s: &str) -> VerboseResult<'_, (usize, Monkey)> {
         let (s, id) = map(tuple((tag("Monkey"), multispace1, u64)), |(_, _, u)| {
             u as usize
         })(s)?;
         let (s, _) = ignore_until_include_newline(s)?;
 
-        let (s, items) = parse_items(s)?;
-        let (s, operation) = parse_operation(s)?;
-        let (s, test_condition) = parse_test_condition(s)?;
+        let (s, items) = context("starting items", parse_items)(s)?;
+        let (s, operation) = context("operation", parse_operation)(s)?;
+        let (s, test_condition) = context("test", parse_test_condition)(s)?;
 
         // eat the next line
         let (s, _) = ignore_until_newline(s)?;
@@ -100,7 +100,14 @@ mod parser {
         ))
     }
 
-    fn parse_operation(s: &str) -> IResult<&str, Operation> {
+    pub fn parse_monkeys(s: &str) -> VerboseResult<'_, Vec<Monkey>> {
+        separated_items(
+            line_ending,
+            map(context("monkey", parse_monkey), |(_id, monkey)| monkey),
+        )(s)
+    }
+
+    fn parse_operation(s: &str) -> VerboseResult<'_, Operation> {
         let (s, _) = tuple((
             multispace1,
             tag("Operation:"),
@@ -115,26 +122,22 @@ mod parser {
 
         // multiply and add
         let (s, operation) = alt((
-            map(
-                separated_pair(alt((tag("*"), tag("+"))), multispace0, tag("old")),
-                |(sign, _)| match sign {
-                    "*" => Operation::Square,
-                    "+" => Operation::Multiply(2),
-                    _ => panic!("shouldn't happen"),
-                },
+            value(
+                Operation::Square,
+                tuple((tag("*"), multispace0, tag("old"))),
+            ),
+            value(
+                Operation::Multiply(2),
+                tuple((tag("+"), multispace0, tag("old"))),
             ),
             map(
                 // "* 12" or "+ 220"
-                separated_pair(
-                    alt((tag("*"), tag("+"))),
-                    multispace0,
-                    map(u16, |u| u as usize),
-                ),
-                |(sign, num)| match sign {
-                    "*" => Operation::Multiply(num),
-                    "+" => Operation::Add(num),
-                    _ => panic!("shouldn't happen"),
-                },
+                preceded(pair(tag("*"), multispace0), map(u16, |u| u as usize)),
+                Operation::Multiply,
+            ),
+            map(
+                preceded(pair(tag("+"), multispace0), map(u16, |u| u as usize)),
+                Operation::Add,
             ),
         ))(s)?;
 
@@ -167,6 +170,8 @@ mod parser {
 mod model {
     use std::cell::RefCell;
 
+    use advent_of_code::helpers::math;
+
     #[derive(Debug, Eq, PartialEq, Clone, Copy)]
     pub enum Operation {
         Multiply(usize),
@@ -199,13 +204,15 @@ mod model {
             (o as f64 / 3.0).floor() as usize
         }
 
-        pub fn inspect_2(&self, item: usize) -> usize {
+        /// Like `inspect` without the relief, computed modulo `modulus`.
+        pub fn inspect_2(&self, item: usize, modulus: usize) -> usize {
+            let (item, m) = (item as u64, modulus as u64);
             let o = match self.operation {
-                Operation::Multiply(n) => item * n,
-                Operation::Add(n) => item + n,
-                Operation::Square => item * item,
+                Operation::Multiply(n) => math::mul_mod(item, n as u64, m),
+                Operation::Add(n) => ((item as u128 + n as u128) % m as u128) as u64,
+                Operation::Square => math::mul_mod(item, item, m),
             };
-            o
+            o as usize
         }
 
         pub fn determine_target(&self, item: usize) -> usize {
@@ -215,13 +222,6 @@ mod model {
             }
         }
 
-        pub fn determine_target_2(&self, item: usize, coprime: usize) -> usize {
-            match item % coprime {
-                0 => self.test.if_true,
-                _ => self.test.if_false,
-            }
-        }
-
         pub fn add_inspect_count(&mut self, how_much: usize) {
             self.count += how_much;
         }
@@ -230,6 +230,29 @@ mod model {
     #[derive(Debug)]
     pub struct Monkeys(pub Vec<RefCell<Monkey>>);
     impl Monkeys {
+        /// Checks that every monkey throws to another monkey that exists.
+        pub fn new(monkeys: Vec<Monkey>) -> eyre::Result<Self> {
+            for (id, monkey) in monkeys.iter().enumerate() {
+                if monkey.test.divisible_by == 0 {
+                    eyre::bail!("Monkey {} tests divisibility by 0", id);
+                }
+                for target in [monkey.test.if_true, monkey.test.if_false] {
+                    if target == id {
+                        eyre::bail!("Monkey {} throws to itself", id);
+                    }
+                    if target >= monkeys.len() {
+                        eyre::bail!(
+                            "Monkey {} throws to monkey {}, but there are only {} monkeys",
+                            id,
+                            target,
+                            monkeys.len()
+                        );
+                    }
+                }
+            }
+            Ok(Self(monkeys.into_iter().map(RefCell::new).collect()))
+        }
+
         fn push_to_monkey_list(&self, id: usize, item: usize) {
             self.0[id].borrow_mut().items.push(item);
         }
@@ -249,25 +272,24 @@ mod model {
             }
         }
 
-        pub fn run_2(&mut self) {
-            let coprime: usize = self
-                .0
-                .iter()
-                .map(|r| r.borrow().test.divisible_by)
-                .fold(1, |a, x| a * x);
+        /// Worry levels only matter modulo each monkey's divisor, so they can be kept modulo the
+        /// lcm of all divisors.
+        pub fn worry_modulus(&self) -> eyre::Result<usize> {
+            let modulus = math::lcm_all(self.0.iter().map(|r| r.borrow().test.divisible_by as u64))
+                .ok_or(eyre::eyre!("The lcm of the divisors overflows"))?;
+            Ok(usize::try_from(modulus)?)
+        }
 
+        pub fn run_2(&mut self, modulus: usize) {
             for m in self.0.iter() {
                 let mut m = m.borrow_mut();
                 let l = m.items.len();
                 m.add_inspect_count(l);
 
                 for item in m.items.iter() {
-                    let new_item = m.inspect_2(*item);
+                    let new_item = m.inspect_2(*item, modulus);
                     let target = m.determine_target(new_item);
-
-                    // Since all divisors are coprime, we can simply keep the modulo
-                    let normalized_item = new_item % coprime;
-                    self.push_to_monkey_list(target, normalized_item);
+                    self.push_to_monkey_list(target, new_item);
                 }
                 m.items.clear();
             }
@@ -277,80 +299,41 @@ mod model {
             self.0.iter().map(|r| r.borrow().count).collect()
         }
 
-        pub fn print_debug(&self, round: usize) {
-            dbg!(round, self);
-        }
-
-        pub fn print_active(&self, round: usize) {
-            dbg!(round, self.get_counts());
+        /// The product of the two highest inspection counts.
+        pub fn monkey_business(&self) -> eyre::Result<usize> {
+            let mut counts = self.get_counts();
+            counts.sort_by(|a, b| b.cmp(a));
+            match counts[..] {
+                [first, second, ..] => Ok(first * second),
+                _ => Err(eyre::eyre!("Monkey business needs at least two monkeys")),
+            }
         }
     }
 }
 
 pub fn part_one(input: &str) -> eyre::Result<usize> {
-    let mut input: &str = &mut input.to_owned();
-    let mut mvec: Vec<Monkey> = Default::default();
-
-    while input.len() > 0 {
-        let (s, (_id, monkey)) = parser::parse_monkey(input).map_err(|e| {
-            dbg!(e);
-            eyre::eyre!("Failed to parse monkey")
-        })?;
-        mvec.push(monkey);
-        if s.len() == 0 {
-            break;
-        } else {
-            let (mut ss, _) = line_ending::<&str, nom::error::Error<&str>>(s).map_err(|e| {
-                dbg!(e);
-                eyre::eyre!("Failed to parse monkey")
-            })?;
-            input = &mut ss;
-        }
-    }
+    let mvec = parse_all(input, parser::parse_monkeys)?;
 
-    let mut monkeys: Monkeys = Monkeys(mvec.into_iter().map(RefCell::new).collect());
-    for i in 0..20 {
+    let mut monkeys = Monkeys::new(mvec)?;
+    for round in 1..=20 {
         monkeys.run();
-        // monkeys.print_debug(i);
+        trace!("after round {}: {:?}", round, monkeys.get_counts());
     }
 
-    let mut counts = monkeys.get_counts();
-    counts.sort_by(Ord::cmp);
-    counts.reverse();
-    Ok(counts[0] * counts[1])
+    monkeys.monkey_business()
 }
 
 pub fn part_two(input: &str) -> eyre::Result<usize> {
-    let mut input: &str = &mut input.to_owned();
-    let mut mvec: Vec<Monkey> = Default::default();
-
-    while input.len() > 0 {
-        let (s, (_id, monkey)) = parser::parse_monkey(input).map_err(|e| {
-            dbg!(e);
-            eyre::eyre!("Failed to parse monkey")
-        })?;
-        mvec.push(monkey);
-        if s.len() == 0 {
-            break;
-        } else {
-            let (mut ss, _) = line_ending::<&str, nom::error::Error<&str>>(s).map_err(|e| {
-                dbg!(e);
-                eyre::eyre!("Failed to parse monkey")
-            })?;
-            input = &mut ss;
-        }
-    }
+    let mvec = parse_all(input, parser::parse_monkeys)?;
 
-    let mut monkeys: Monkeys = Monkeys(mvec.into_iter().map(RefCell::new).collect());
-    for i in 0..10000 {
-        monkeys.run_2();
-        // monkeys.print_debug(i);
+    let mut monkeys = Monkeys::new(mvec)?;
+    let modulus = monkeys.worry_modulus()?;
+    for round in 1..=10000 {
+        monkeys.run_2(modulus);
+        trace!("after round {}: {:?}", round, monkeys.get_counts());
     }
 
-    let mut counts = monkeys.get_counts();
-    counts.sort_by(Ord::cmp);
-    counts.reverse();
-    Ok(counts[0] * counts[1])
+    monkeys.monkey_business()
 }
 
 fn main() {
@@ -374,4 +357,30 @@ mod tests {
         let input = advent_of_code::read_file("examples", 11);
         assert_eq!(part_two(&input).unwrap(), 2713310158);
     }
+
+    #[test]
+    fn test_invalid_targets() {
+        let input = advent_of_code::read_file("examples", 11);
+        let e = part_one(&input.replacen("throw to monkey 2", "throw to monkey 9", 1)).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Monkey 0 throws to monkey 9, but there are only 4 monkeys"
+        );
+        let e = part_two(&input.replacen("throw to monkey 2", "throw to monkey 0", 1)).unwrap_err();
+        assert_eq!(e.to_string(), "Monkey 0 throws to itself");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = advent_of_code::read_file("examples", 11);
+        let e = part_one(&input.replacen("old + 6", "old ^ 6", 1))
+            .unwrap_err()
+            .to_string();
+        assert!(e.starts_with("Parse error at line 10, column 24"), "{}", e);
+        assert!(
+            e.contains("while parsing operation (line 10, column 1)"),
+            "{}",
+            e
+        );
+    }
 }
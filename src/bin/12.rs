@@ -1,35 +1,21 @@
 use std::str::FromStr;
 
-use model::{HillMap, Walk, XY};
+use advent_of_code::helpers::search::{bfs, SearchResult};
+use model::{HillMap, XY};
 
 mod model {
-    use std::{
-        collections::{BinaryHeap, HashMap},
-        fmt::Display,
-        str::FromStr,
-    };
-
-    #[derive(Debug, Hash, Clone, Copy, Default, Eq, PartialEq)]
-    pub struct XY(usize, usize);
-    impl Display for XY {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "({},{})", self.0, self.1)
-        }
-    }
+    use std::str::FromStr;
 
-    #[derive(Clone)]
-    pub struct Contour(Vec<Vec<isize>>);
-    impl Contour {
-        pub fn at(&self, i: usize, j: usize) -> Option<isize> {
-            self.0.get(j).and_then(|v| v.get(i)).map(|u| *u)
-        }
-    }
+    use advent_of_code::helpers::{error::invalid, grid::Grid, point::Point};
 
-    fn char_to_isize(c: char) -> isize {
+    pub type XY = Point<usize>;
+
+    fn char_to_isize(c: char) -> eyre::Result<isize> {
         match c {
-            'S' => 0,
-            'E' => 25,
-            _ => (c as u8 - 'a' as u8) as isize,
+            'S' => Ok(0),
+            'E' => Ok(25),
+            'a'..='z' => Ok((c as u8 - b'a') as isize),
+            _ => Err(invalid("elevation", c, "a to z, S or E")),
         }
     }
 
@@ -37,35 +23,27 @@ mod model {
         type Err = eyre::Report;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let lines: Vec<&str> = s.lines().collect();
+            let chars = Grid::parse(s, |c| char_to_isize(c).map(|_| c))?;
 
-            let mut start: XY = Default::default();
+            let mut start: Option<XY> = None;
             let mut lowest: Vec<XY> = Default::default();
-            let mut end: XY = Default::default();
-            let mut contour: Vec<Vec<isize>> = Default::default();
-            for (j, line) in lines.iter().enumerate() {
-                let mut row: Vec<isize> = Default::default();
-                for (i, c) in line.chars().enumerate() {
-                    if c == 'S' {
-                        start = XY(i, j);
-                    } else if c == 'E' {
-                        end = XY(i, j);
-                    }
-                    if c == 'S' || c == 'a' {
-                        lowest.push(XY(i, j));
-                    }
-                    row.push(char_to_isize(c));
+            let mut end: Option<XY> = None;
+            for ((i, j), c) in chars.iter() {
+                if *c == 'S' {
+                    start = Some(XY::new(i, j));
+                } else if *c == 'E' {
+                    end = Some(XY::new(i, j));
+                }
+                if *c == 'S' || *c == 'a' {
+                    lowest.push(XY::new(i, j));
                 }
-                contour.push(row);
             }
 
             let hillmap = HillMap {
-                x: contour[0].len(),
-                y: contour.len(),
                 lowest,
-                contour: Contour(contour),
-                start,
-                end,
+                contour: chars.map(|c| char_to_isize(*c).unwrap_or_default()),
+                start: start.ok_or(eyre::eyre!("The map has no start S"))?,
+                end: end.ok_or(eyre::eyre!("The map has no end E"))?,
             };
             Ok(hillmap)
         }
@@ -73,133 +51,61 @@ mod model {
 
     #[derive(Clone)]
     pub struct HillMap {
-        x: usize,
-        y: usize,
         pub lowest: Vec<XY>,
-        contour: Contour,
+        contour: Grid<isize>,
         pub start: XY,
         pub end: XY,
     }
     impl HillMap {
-        pub fn movable_neighbours(&self, &XY(i, j): &XY) -> Vec<(XY, isize)> {
-            let me = self
-                .contour
-                .at(i, j)
-                .expect(&format!("Cannot find XY({}, {})", i, j));
-
-            [
-                (i as isize - 1, j as isize),
-                (i as isize, j as isize - 1),
-                (i as isize + 1, j as isize),
-                (i as isize, j as isize + 1),
-            ]
-            .into_iter()
-            // make sure neighbour is in grid
-            .filter(|(i, j)| *i >= 0 && *j >= 0 && *i < self.x as isize && *j < self.y as isize)
-            .map(|(i, j)| {
-                let i = i as usize;
-                let j = j as usize;
-                // dbg!(i, j);
-                (XY(i, j), self.contour.at(i, j).unwrap())
-            })
-            // make sure neighbour is not too high
-            .filter(|(_xy, height)| height - me <= 1)
-            .collect()
-        }
-    }
+        /// Positions outside the map have no neighbours.
+        pub fn movable_neighbours(&self, xy: &XY) -> Vec<(XY, isize)> {
+            let Some(&me) = self.contour.get(xy.x, xy.y) else {
+                return vec![];
+            };
 
-    #[derive(Eq, PartialEq)]
-    pub struct Visitable(XY, usize);
-    impl PartialOrd for Visitable {
-        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-            other.1.partial_cmp(&self.1)
+            self.contour
+                .neighbours4(xy.x, xy.y)
+                .map(|((i, j), height)| (XY::new(i, j), *height))
+                // make sure neighbour is not too high
+                .filter(|(_xy, height)| height - me <= 1)
+                .collect()
         }
     }
-    impl Ord for Visitable {
-        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-            other.1.cmp(&self.1)
-        }
-    }
-
-    pub struct Walk {
-        pub visited: HashMap<XY, usize>,
-        visit_order: BinaryHeap<Visitable>,
-        pub hillmap: HillMap,
-    }
-
-    impl Walk {
-        pub fn new(hillmap: HillMap) -> Self {
-            Self {
-                visited: Default::default(),
-                visit_order: BinaryHeap::new(),
-                hillmap,
-            }
-        }
-
-        pub fn walk(&mut self) {
-            self.visit_order.push(Visitable(self.hillmap.start, 0));
-            loop {
-                let current = self.visit_order.pop();
-                if current.is_none() {
-                    break;
-                }
-                let Visitable(xy, steps) = current.unwrap();
-                // println!("Visiting {} at Step {}", xy, steps);
-
-                // self.visited.insert(xy, steps);
-
-                if xy == self.hillmap.end {
-                    break;
-                }
+}
 
-                let neighbours = self.hillmap.movable_neighbours(&xy);
-                let neighbours = neighbours
-                    .iter()
-                    // ignore XY that we have visited
-                    .filter(|(xy, _)| !self.visited.contains_key(xy))
-                    .collect::<Vec<_>>();
-                neighbours.iter().for_each(|(xy, _)| {
-                    self.visit_order.push(Visitable(*xy, steps + 1));
-                    self.visited.insert(*xy, steps + 1);
-                });
-            }
-        }
-    }
+/// Shortest walks from any of `starts` until the best signal location is reached.
+fn walk(hillmap: &HillMap, starts: impl IntoIterator<Item = XY>) -> SearchResult<XY, usize> {
+    bfs(
+        starts,
+        |xy| {
+            hillmap
+                .movable_neighbours(xy)
+                .into_iter()
+                .map(|(xy, _height)| xy)
+        },
+        |xy| *xy == hillmap.end,
+    )
 }
 
 pub fn part_one(input: &str) -> eyre::Result<usize> {
     let hillmap = HillMap::from_str(input)?;
-    let mut walk = Walk::new(hillmap);
-    walk.walk();
-    let how_much = walk.visited.get(&walk.hillmap.end);
-    Ok(*how_much.unwrap())
+    walk(&hillmap, [hillmap.start])
+        .goal_distance()
+        .ok_or(eyre::eyre!(
+            "Cannot reach {} from {}",
+            hillmap.end,
+            hillmap.start
+        ))
 }
 
 pub fn part_two(input: &str) -> eyre::Result<usize> {
     let hillmap = HillMap::from_str(input)?;
-
-    let mut min_steps: Option<(XY, usize)> = None;
-
-    for xy in &hillmap.lowest {
-        let mut hillmap = hillmap.clone();
-        hillmap.start = *xy;
-
-        let mut walk = Walk::new(hillmap);
-        walk.walk();
-        let how_much = walk.visited.get(&walk.hillmap.end);
-        if let Some(h) = how_much {
-            match min_steps {
-                Some((_ij, m)) => {
-                    if *h < m {
-                        min_steps = Some((*xy, *h))
-                    }
-                }
-                None => min_steps = Some((*xy, *h)),
-            }
-        }
-    }
-
-    Ok(min_steps.unwrap().1)
+    walk(&hillmap, hillmap.lowest.iter().copied())
+        .goal_distance()
+        .ok_or(eyre::eyre!(
+            "Cannot reach {} from any lowest square",
+            hillmap.end
+        ))
 }
 
 fn main() {
@@ -223,4 +129,25 @@ mod tests {
         let input = advent_of_code::read_file("examples", 12);
         assert_eq!(part_two(&input).unwrap(), 29);
     }
+
+    #[test]
+    fn test_path() {
+        let input = advent_of_code::read_file("examples", 12);
+        let hillmap = HillMap::from_str(&input).unwrap();
+        let path = walk(&hillmap, [hillmap.start]).goal_path().unwrap();
+        assert_eq!(path.len(), 32);
+        assert_eq!(path.first(), Some(&hillmap.start));
+        assert_eq!(path.last(), Some(&hillmap.end));
+        assert!(path.windows(2).all(|w| w[0].manhattan(&w[1]) == 1));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let e = part_one("Sab\nEc#\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "Invalid grid at line 2: Invalid elevation '#', expected a to z, S or E"
+        );
+        assert!(part_one("Sab\nabc\n").is_err());
+    }
 }
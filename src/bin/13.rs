@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use advent_of_code::helpers::parse::parse_all;
 use model::Packet;
 
 use crate::logic::is_right_order;
@@ -8,15 +11,17 @@ mod parser {
         bytes::complete::tag,
         character::complete::{digit1, line_ending},
         combinator::{map, map_res},
-        multi::separated_list0,
-        sequence::tuple,
+        multi::{many1, separated_list0},
+        sequence::{pair, separated_pair, tuple},
         IResult,
     };
 
+    use advent_of_code::helpers::parse::separated_items;
+
     use crate::model::Packet;
 
     pub fn parse_num(s: &str) -> IResult<&str, Packet> {
-        map_res(digit1, |s: &str| s.parse::<usize>().map(|u| Packet::Num(u)))(s)
+        map_res(digit1, |s: &str| s.parse::<usize>().map(Packet::Num))(s)
     }
 
     pub fn parse_items(s: &str) -> IResult<&str, Vec<Packet>> {
@@ -29,23 +34,23 @@ mod parser {
         })(s)
     }
 
-    pub fn parse_pairs(s: &str) -> IResult<&str, (Packet, Packet)> {
-        map(
-            tuple((
-                parse_list,
-                line_ending,
-                parse_list,
-                line_ending,
-                line_ending,
-            )),
-            |(m, _, n, _, _)| (m, n),
+    pub fn parse_pairs(s: &str) -> IResult<&str, Vec<(Packet, Packet)>> {
+        separated_items(
+            pair(line_ending, line_ending),
+            separated_pair(parse_list, line_ending, parse_list),
         )(s)
     }
+
+    pub fn parse_packets(s: &str) -> IResult<&str, Vec<Packet>> {
+        separated_items(many1(line_ending), parse_list)(s)
+    }
 }
 
 mod model {
     use std::str::FromStr;
 
+    use advent_of_code::helpers::parse::parse_all;
+
     use crate::{
         logic::{self, Outcome},
         parser,
@@ -60,28 +65,20 @@ mod model {
         type Err = eyre::Report;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            parser::parse_list(s)
-                .map(|(_, p)| p)
-                .map_err(|e| eyre::eyre!(e.to_string()))
+            parse_all(s, parser::parse_list)
         }
     }
 
     impl PartialEq for Packet {
         fn eq(&self, other: &Self) -> bool {
-            match logic::comp((&self, &other)) {
-                Outcome::Continue => true,
-                _ => false,
-            }
+            matches!(logic::comp((self, other)), Outcome::Continue)
         }
     }
     impl Eq for Packet {}
 
     impl PartialOrd for Packet {
         fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-            Some(match logic::is_right_order((self, other)) {
-                true => std::cmp::Ordering::Less,
-                false => std::cmp::Ordering::Greater,
-            })
+            Some(self.cmp(other))
         }
     }
     impl Ord for Packet {
@@ -118,8 +115,8 @@ mod logic {
             (s @ Packet::Num(_), t @ Packet::List(_)) => comp((&Packet::List(vec![s.clone()]), t)),
             (s @ Packet::List(_), t @ Packet::Num(_)) => comp((s, &Packet::List(vec![t.clone()]))),
             (Packet::List(s), Packet::List(t)) => {
-                let mut s = s.into_iter();
-                let mut t = t.into_iter();
+                let mut s = s.iter();
+                let mut t = t.iter();
                 loop {
                     let i = s.next();
                     let j = t.next();
@@ -131,7 +128,7 @@ mod logic {
                             let r = comp((s, t));
                             match r {
                                 Outcome::Continue => {}
-                                a @ _ => return a,
+                                a => return a,
                             }
                         }
                     }
@@ -150,16 +147,7 @@ mod logic {
 }
 
 pub fn part_one(input: &str) -> eyre::Result<usize> {
-    let mut input = input;
-    let mut pairs: Vec<(Packet, Packet)> = Default::default();
-    loop {
-        let (s, pair) = parser::parse_pairs(input).map_err(|e| eyre::eyre!(e.to_string()))?;
-        pairs.push(pair);
-        input = s;
-        if input.is_empty() {
-            break;
-        }
-    }
+    let pairs = parse_all(input, parser::parse_pairs)?;
 
     Ok(pairs
         .iter()
@@ -170,20 +158,9 @@ pub fn part_one(input: &str) -> eyre::Result<usize> {
 }
 
 pub fn part_two(input: &str) -> eyre::Result<usize> {
-    let mut packets = input
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|s| {
-            parser::parse_list(s)
-                .map_err(|e| eyre::eyre!(e.to_string()))
-                .map(|(_, p)| p)
-        })
-        .collect::<eyre::Result<Vec<Packet>>>()?;
-
-    let divisor = vec![
-        parser::parse_list("[[2]]").map(|(_, p)| p).unwrap(),
-        parser::parse_list("[[6]]").map(|(_, p)| p).unwrap(),
-    ];
+    let mut packets = parse_all(input, parser::parse_packets)?;
+
+    let divisor = vec![Packet::from_str("[[2]]")?, Packet::from_str("[[6]]")?];
 
     packets.append(&mut divisor.clone());
     packets.sort();
@@ -221,4 +198,10 @@ mod tests {
         let input = advent_of_code::read_file("examples", 13);
         assert_eq!(part_two(&input).unwrap(), 140);
     }
+
+    #[test]
+    fn test_parse_error() {
+        let e = part_one("[1,2]\n[3,x]\n").unwrap_err().to_string();
+        assert!(e.starts_with("Parse error at line 2, column 3"), "{}", e);
+    }
 }
@@ -2,6 +2,7 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
+use advent_of_code::aoc;
 use std::io::Write;
 use std::path::PathBuf;
 use std::{env::temp_dir, io, process::Command};
@@ -9,7 +10,8 @@ use std::{fs, process};
 
 struct Args {
     day: u8,
-    year: Option<i16>,
+    year: Option<i32>,
+    wait: bool,
 }
 
 fn parse_args() -> Result<Args, pico_args::Error> {
@@ -17,6 +19,7 @@ fn parse_args() -> Result<Args, pico_args::Error> {
     Ok(Args {
         day: args.free_from_str()?,
         year: args.opt_value_from_str(["-y", "--year"])?,
+        wait: args.contains(["-w", "--wait"]),
     })
 }
 
@@ -47,6 +50,18 @@ fn main() {
         }
     };
 
+    // without `--year`, aoc-cli picks the event unless we have to wait for the upcoming one.
+    let year = args.year.or_else(|| {
+        args.wait
+            .then(|| aoc::upcoming_event_year_at(aoc::now_timestamp()))
+    });
+    if let Some(year) = year {
+        if let Err(e) = aoc::ensure_unlocked(&aoc::SystemClock, year, args.day, args.wait) {
+            eprintln!("{}", e);
+            exit_with_status(1, &tmp_file_path);
+        }
+    }
+
     let day_padded = format!("{:02}", args.day);
     let input_path = format!("src/inputs/{}.txt", day_padded);
 
@@ -56,9 +71,13 @@ fn main() {
         exit_with_status(1, &tmp_file_path);
     }
 
+    if let Some(warning) = aoc::session_age().ok().and_then(aoc::session_age_warning) {
+        eprintln!("{}", warning);
+    }
+
     let mut cmd_args = vec![];
 
-    if let Some(year) = args.year {
+    if let Some(year) = year {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
@@ -82,6 +101,7 @@ fn main() {
                 .write_all(&cmd_output.stderr)
                 .expect("could not write cmd stderr to pipe.");
             if !cmd_output.status.success() {
+                eprintln!("Type `cargo session validate` to check your session cookie.");
                 exit_with_status(1, &tmp_file_path);
             }
         }
@@ -0,0 +1,306 @@
+//! `cargo leaderboard`: fetches, saves and prints a private leaderboard, as a table or markdown.
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use advent_of_code::aoc;
+use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use serde_json::Value;
+
+struct Args {
+    file: Option<PathBuf>,
+    id: Option<String>,
+    year: Option<i32>,
+    save: Option<PathBuf>,
+    markdown: bool,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        id: args.opt_value_from_str("--id")?,
+        year: args.opt_value_from_str(["-y", "--year"])?,
+        save: args.opt_value_from_str("--save")?,
+        markdown: args.contains(["-m", "--markdown"]),
+        file: args.opt_free_from_str()?,
+    })
+}
+
+/// Star timestamps for a single day: (part one, part two).
+type DayStars = (Option<i64>, Option<i64>);
+
+struct Member {
+    name: String,
+    local_score: u64,
+    stars: u64,
+    last_star_ts: i64,
+    days: BTreeMap<u8, DayStars>,
+}
+
+struct Leaderboard {
+    year: i32,
+    members: Vec<Member>,
+}
+
+fn star_ts(level: &Value, part: &str) -> Option<i64> {
+    level.get(part)?.get("get_star_ts")?.as_i64()
+}
+
+fn parse_member(id: &str, value: &Value) -> eyre::Result<Member> {
+    let name = match value.get("name").and_then(Value::as_str) {
+        Some(name) => name.to_owned(),
+        None => format!("(anonymous user #{})", id),
+    };
+    let mut days = BTreeMap::new();
+    if let Some(levels) = value.get("completion_day_level").and_then(Value::as_object) {
+        for (day, level) in levels {
+            let day: u8 = day
+                .parse()
+                .map_err(|_| eyre::eyre!("Invalid day {:?} for member {}", day, id))?;
+            days.insert(day, (star_ts(level, "1"), star_ts(level, "2")));
+        }
+    }
+    Ok(Member {
+        name,
+        local_score: value
+            .get("local_score")
+            .and_then(Value::as_u64)
+            .unwrap_or(0),
+        stars: value.get("stars").and_then(Value::as_u64).unwrap_or(0),
+        last_star_ts: value
+            .get("last_star_ts")
+            .and_then(Value::as_i64)
+            .unwrap_or(0),
+        days,
+    })
+}
+
+fn parse_leaderboard(json: &str) -> eyre::Result<Leaderboard> {
+    let value: Value = serde_json::from_str(json)?;
+    let year = value
+        .get("event")
+        .and_then(Value::as_str)
+        .ok_or(eyre::eyre!("Leaderboard is missing \"event\""))?
+        .parse()?;
+    let mut members = value
+        .get("members")
+        .and_then(Value::as_object)
+        .ok_or(eyre::eyre!("Leaderboard is missing \"members\""))?
+        .iter()
+        .map(|(id, member)| parse_member(id, member))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.last_star_ts.cmp(&b.last_star_ts))
+    });
+    Ok(Leaderboard { year, members })
+}
+
+fn fetch_leaderboard(year: i32, id: &str) -> eyre::Result<String> {
+    let url = format!(
+        "{}/{}/leaderboard/private/view/{}.json",
+        aoc::server(),
+        year,
+        id
+    );
+    let cookie = aoc::read_session()?;
+    eprintln!("Fetching leaderboard with >curl {}", url);
+    let output = aoc::curl_with_session(&cookie, &["--silent", "--fail", &url])
+        .map_err(|e| eyre::eyre!("failed to spawn curl: {}", e))?;
+    if !output.status.success() {
+        eyre::bail!("could not fetch leaderboard (type `cargo session validate` to check your session cookie)");
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Completion times relative to unlock for both parts, plus the part 1 -> part 2 delta.
+fn day_times(year: i32, day: u8, stars: &DayStars) -> [Option<i64>; 3] {
+    let unlock = aoc::unlock_timestamp(year, day);
+    let (one, two) = *stars;
+    [
+        one.map(|t| t - unlock),
+        two.map(|t| t - unlock),
+        one.zip(two).map(|(one, two)| two - one),
+    ]
+}
+
+fn fmt_time(time: Option<i64>) -> String {
+    time.map(aoc::format_duration).unwrap_or_else(|| "-".into())
+}
+
+fn print_terminal(leaderboard: &Leaderboard) {
+    println!(
+        "🎄 {}Leaderboard {}{} 🎄",
+        ANSI_BOLD, leaderboard.year, ANSI_RESET
+    );
+    println!("{:>3}  {:>6}  {:>5}  Member", "#", "Score", "Stars");
+    for (rank, member) in leaderboard.members.iter().enumerate() {
+        println!(
+            "{:>3}  {:>6}  {:>5}  {}",
+            rank + 1,
+            member.local_score,
+            member.stars,
+            member.name
+        );
+    }
+
+    for member in leaderboard.members.iter().filter(|m| !m.days.is_empty()) {
+        println!("---");
+        println!("{}{}{}", ANSI_BOLD, member.name, ANSI_RESET);
+        println!(
+            "{}Day  {:>12}  {:>12}  {:>12}{}",
+            ANSI_ITALIC, "Part 1", "Part 2", "Delta", ANSI_RESET
+        );
+        for (day, stars) in member.days.iter() {
+            let [one, two, delta] = day_times(leaderboard.year, *day, stars);
+            println!(
+                "{:>3}  {:>12}  {:>12}  {:>12}",
+                day,
+                fmt_time(one),
+                fmt_time(two),
+                fmt_time(delta)
+            );
+        }
+    }
+}
+
+/// Member names are user-chosen, so a `|` in them must not end the table cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn render_markdown(leaderboard: &Leaderboard) -> String {
+    let mut out = format!("### Leaderboard {}\n\n", leaderboard.year);
+    out.push_str("| # | Member | Score | Stars |\n|--:|:--|--:|--:|\n");
+    for (rank, member) in leaderboard.members.iter().enumerate() {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            rank + 1,
+            escape_cell(&member.name),
+            member.local_score,
+            member.stars
+        ));
+    }
+
+    out.push_str("\n| Member | Day | Part 1 | Part 2 | Delta |\n|:--|--:|--:|--:|--:|\n");
+    for member in leaderboard.members.iter() {
+        for (day, stars) in member.days.iter() {
+            let [one, two, delta] = day_times(leaderboard.year, *day, stars);
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                escape_cell(&member.name),
+                day,
+                fmt_time(one),
+                fmt_time(two),
+                fmt_time(delta)
+            ));
+        }
+    }
+    out
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let json = match (&args.file, &args.id) {
+        (Some(file), _) => fs::read_to_string(file)
+            .map_err(|e| eyre::eyre!("could not read leaderboard file {:?}: {}", file, e)),
+        (None, Some(id)) => {
+            let year = args
+                .year
+                .unwrap_or_else(|| aoc::event_year_at(aoc::now_timestamp()));
+            fetch_leaderboard(year, id)
+        }
+        (None, None) => {
+            eprintln!("Need to specify a leaderboard file or id. example: `cargo leaderboard leaderboard.json` or `cargo leaderboard --id 123456`");
+            process::exit(1);
+        }
+    };
+
+    let json = match json {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    if let Some(path) = &args.save {
+        if let Err(e) = fs::write(path, &json) {
+            eprintln!("could not save leaderboard to {:?}: {}", path, e);
+            process::exit(1);
+        }
+    }
+
+    match parse_leaderboard(&json) {
+        Ok(leaderboard) if args.markdown => print!("{}", render_markdown(&leaderboard)),
+        Ok(leaderboard) => print_terminal(&leaderboard),
+        Err(e) => {
+            eprintln!("could not parse leaderboard: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"{
+        "event": "2022",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "local_score": 10, "stars": 3, "last_star_ts": 1669957300,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 1669871400 }, "2": { "get_star_ts": 1669871700 } },
+                    "2": { "1": { "get_star_ts": 1669957300 } }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "local_score": 4, "stars": 2, "last_star_ts": 1669880000,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 1669875000 }, "2": { "get_star_ts": 1669880000 } }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_leaderboard() {
+        let leaderboard = parse_leaderboard(EXAMPLE).unwrap();
+        assert_eq!(leaderboard.year, 2022);
+        let names: Vec<&str> = leaderboard.members.iter().map(|m| &m.name[..]).collect();
+        assert_eq!(names, vec!["alice", "(anonymous user #2)"]);
+
+        let alice = &leaderboard.members[0];
+        assert_eq!(
+            day_times(2022, 1, &alice.days[&1]),
+            [Some(600), Some(900), Some(300)]
+        );
+        assert_eq!(day_times(2022, 2, &alice.days[&2]), [Some(100), None, None]);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let leaderboard = parse_leaderboard(EXAMPLE).unwrap();
+        let markdown = render_markdown(&leaderboard);
+        assert!(markdown.contains("| 1 | alice | 10 | 3 |\n"));
+        assert!(markdown.contains("| alice | 1 | 0:10:00 | 0:15:00 | 0:05:00 |\n"));
+        assert!(markdown.contains("| alice | 2 | 0:01:40 | - | - |\n"));
+
+        let mut leaderboard = leaderboard;
+        leaderboard.members[0].name = "al|ce".to_owned();
+        let markdown = render_markdown(&leaderboard);
+        assert!(markdown.contains("| 1 | al\\|ce | 10 | 3 |\n"));
+    }
+}
@@ -0,0 +1,273 @@
+//! `cargo read`: renders a stored puzzle description in the terminal.
+use std::{fs, process};
+
+use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag};
+
+const ANSI_CODE: &str = "\x1b[36m";
+const CODE_INDENT: &str = "    ";
+
+struct Args {
+    day: u8,
+    examples: bool,
+    width: usize,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    Ok(Args {
+        examples: args.contains(["-e", "--examples"]),
+        width: args.opt_value_from_str(["-w", "--width"])?.unwrap_or(80),
+        day: args.free_from_str()?,
+    })
+}
+
+/// Loads the stored description. `DD_2.txt` is saved after solving part one and usually
+/// contains the full page, in which case it replaces `DD.txt` instead of being appended.
+fn read_description(day: u8) -> Result<String, std::io::Error> {
+    let part_one = fs::read_to_string(format!("src/puzzles/{:02}.txt", day))?;
+    match fs::read_to_string(format!("src/puzzles/{:02}_2.txt", day)) {
+        Ok(part_two) if part_two.lines().next() == part_one.lines().next() => Ok(part_two),
+        Ok(part_two) => Ok(format!("{}\n\n{}", part_one, part_two)),
+        Err(_) => Ok(part_one),
+    }
+}
+
+/// Collects words into lines of at most `width` visible characters.
+struct Wrapper {
+    width: usize,
+    indent: String,
+    out: String,
+    line_len: usize,
+    word: String,
+    word_len: usize,
+    word_style: String,
+}
+
+impl Wrapper {
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            indent: String::new(),
+            out: String::new(),
+            line_len: 0,
+            word: String::new(),
+            word_len: 0,
+            word_style: String::new(),
+        }
+    }
+
+    fn push_text(&mut self, text: &str, style: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.flush_word();
+                continue;
+            }
+            if self.word_style != style {
+                if !self.word_style.is_empty() {
+                    self.word.push_str(ANSI_RESET);
+                }
+                self.word.push_str(style);
+                self.word_style = style.to_owned();
+            }
+            self.word.push(c);
+            self.word_len += 1;
+        }
+    }
+
+    fn flush_word(&mut self) {
+        if self.word_len == 0 {
+            return;
+        }
+        if !self.word_style.is_empty() {
+            self.word.push_str(ANSI_RESET);
+        }
+        if self.line_len > 0 && self.line_len + 1 + self.word_len > self.width {
+            self.newline();
+        }
+        if self.line_len == 0 {
+            self.out.push_str(&self.indent);
+            self.line_len = self.indent.chars().count();
+        } else {
+            self.out.push(' ');
+            self.line_len += 1;
+        }
+        self.out.push_str(&self.word);
+        self.line_len += self.word_len;
+        self.word.clear();
+        self.word_len = 0;
+        self.word_style.clear();
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        self.line_len = 0;
+    }
+
+    fn end_block(&mut self) {
+        self.flush_word();
+        if self.line_len > 0 {
+            self.newline();
+        }
+    }
+
+    fn push_raw(&mut self, s: &str) {
+        self.end_block();
+        self.out.push_str(s);
+    }
+}
+
+fn render(markdown: &str, width: usize) -> String {
+    let mut wrapper = Wrapper::new(width);
+    let mut styles: Vec<&str> = vec![];
+    let mut code_block: Option<String> = None;
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::Heading(..)) => {
+                wrapper.end_block();
+                wrapper.newline();
+                styles.push(ANSI_BOLD);
+            }
+            Event::Start(Tag::Emphasis) | Event::Start(Tag::Strong) => styles.push(ANSI_BOLD),
+            Event::Start(Tag::Link(..)) => styles.push(ANSI_ITALIC),
+            Event::End(Tag::Heading(..)) => {
+                styles.pop();
+                wrapper.end_block();
+                wrapper.newline();
+            }
+            Event::End(Tag::Emphasis) | Event::End(Tag::Strong) | Event::End(Tag::Link(..)) => {
+                styles.pop();
+            }
+            Event::Start(Tag::Item) => {
+                wrapper.end_block();
+                wrapper.out.push('•');
+                wrapper.line_len = 1;
+                wrapper.indent = "  ".into();
+            }
+            Event::End(Tag::Item) => {
+                wrapper.end_block();
+                wrapper.indent.clear();
+            }
+            Event::End(Tag::Paragraph) | Event::End(Tag::List(_)) => {
+                wrapper.end_block();
+                if wrapper.indent.is_empty() {
+                    wrapper.newline();
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                wrapper.end_block();
+                code_block = Some(String::new());
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                for line in code_block.take().unwrap_or_default().trim_end().lines() {
+                    wrapper.push_raw(&format!(
+                        "{}{}{}{}\n",
+                        CODE_INDENT, ANSI_CODE, line, ANSI_RESET
+                    ));
+                }
+                wrapper.newline();
+            }
+            Event::Text(text) if code_block.is_some() => {
+                code_block
+                    .iter_mut()
+                    .for_each(|block| block.push_str(&text));
+            }
+            Event::Text(text) => {
+                wrapper.push_text(&text, &styles.concat());
+            }
+            Event::Code(code) => {
+                let mut style = styles.concat();
+                style.push_str(ANSI_CODE);
+                wrapper.push_text(&code, &style);
+            }
+            Event::SoftBreak => wrapper.flush_word(),
+            Event::HardBreak => wrapper.end_block(),
+            _ => {}
+        }
+    }
+    wrapper.end_block();
+    wrapper.out.trim_matches('\n').to_owned()
+}
+
+fn code_blocks(markdown: &str) -> Vec<String> {
+    let mut blocks = vec![];
+    let mut current: Option<String> = None;
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(_) | CodeBlockKind::Indented)) => {
+                current = Some(String::new());
+            }
+            Event::End(Tag::CodeBlock(_)) => blocks.extend(current.take()),
+            Event::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(_) => {
+            eprintln!("Need to specify a day (as integer). example: `cargo read 7`");
+            process::exit(1);
+        }
+    };
+
+    let description = match read_description(args.day) {
+        Ok(description) => description,
+        Err(e) => {
+            eprintln!(
+                "Failed to read puzzle description for day {}: {}",
+                args.day, e
+            );
+            process::exit(1);
+        }
+    };
+
+    if args.examples {
+        for block in code_blocks(&description) {
+            println!("{}", block.trim_end());
+            println!();
+        }
+    } else {
+        println!("{}", render(&description, args.width));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_blocks() {
+        let description = read_description(4).unwrap();
+        let blocks = code_blocks(&description);
+        assert_eq!(
+            blocks[0].trim_end(),
+            advent_of_code::read_file("examples", 4).trim_end()
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(
+            "\\--- Day 1 ---\n----------\n\nThe *most* `Calories` of all.",
+            12,
+        );
+        assert_eq!(
+            rendered,
+            format!(
+                "{b}---{r} {b}Day{r} {b}1{r}\n{b}---{r}\n\nThe {b}most{r}\n{c}Calories{r} of\nall.",
+                b = ANSI_BOLD,
+                c = ANSI_CODE,
+                r = ANSI_RESET
+            )
+        );
+    }
+}
@@ -2,10 +2,11 @@
  * This file contains template code.
  * There is no need to edit this file unless you want to change template functionality.
  */
+use advent_of_code::aoc;
 use std::{
     fs::{File, OpenOptions},
     io::Write,
-    process,
+    process::{self, Command},
 };
 
 const MODULE_TEMPLATE: &str = r###"pub fn part_one(input: &str) -> Option<u32> {
@@ -40,9 +41,19 @@ mod tests {
 }
 "###;
 
-fn parse_args() -> Result<u8, pico_args::Error> {
+struct Args {
+    day: u8,
+    year: Option<i32>,
+    wait: bool,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
     let mut args = pico_args::Arguments::from_env();
-    args.free_from_str()
+    Ok(Args {
+        day: args.free_from_str()?,
+        year: args.opt_value_from_str(["-y", "--year"])?,
+        wait: args.contains(["-w", "--wait"]),
+    })
 }
 
 fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
@@ -50,18 +61,35 @@ fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
 }
 
 fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
 fn main() {
-    let day = match parse_args() {
-        Ok(day) => day,
+    let args = match parse_args() {
+        Ok(args) => args,
         Err(_) => {
             eprintln!("Need to specify a day (as integer). example: `cargo scaffold 7`");
             process::exit(1);
         }
     };
 
+    let day = args.day;
+    // without `--year`, only `--wait` needs to know the event: the upcoming one.
+    let year = args.year.or_else(|| {
+        args.wait
+            .then(|| aoc::upcoming_event_year_at(aoc::now_timestamp()))
+    });
+    if let Some(year) = year {
+        if let Err(e) = aoc::ensure_unlocked(&aoc::SystemClock, year, day, args.wait) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+
     let day_padded = format!("{:02}", day);
 
     let input_path = format!("src/inputs/{}.txt", day_padded);
@@ -106,6 +134,21 @@ fn main() {
         }
     }
 
+    // `--wait` is meant to be started ahead of unlock: fetch the input right away as well.
+    if let (true, Some(year)) = (args.wait, year) {
+        println!("---");
+        let status = Command::new("cargo")
+            .args(["download", &day.to_string(), "--year", &year.to_string()])
+            .status();
+        if !matches!(status, Ok(s) if s.success()) {
+            eprintln!(
+                "Failed to download input. Type `cargo download {} --year {}` to retry.",
+                day, year
+            );
+            process::exit(1);
+        }
+    }
+
     println!("---");
     println!(
         "🎄 Type `cargo solve {}` to run your solution.",
@@ -0,0 +1,147 @@
+//! `cargo session`: stores, shows and validates the adventofcode.com session cookie.
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    process,
+};
+
+use advent_of_code::aoc;
+
+enum Args {
+    /// Without a cookie, it is read from stdin so that it stays out of the shell history.
+    Set(Option<String>),
+    Show,
+    Validate,
+}
+
+fn parse_args() -> Result<Args, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    let subcommand: String = args.free_from_str()?;
+    match subcommand.as_str() {
+        "set" => Ok(Args::Set(args.opt_free_from_str()?)),
+        "show" => Ok(Args::Show),
+        "validate" => Ok(Args::Validate),
+        _ => Err(pico_args::Error::ArgumentParsingFailed {
+            cause: format!("unknown subcommand \"{}\"", subcommand),
+        }),
+    }
+}
+
+fn print_age_warning() {
+    if let Some(warning) = aoc::session_age().ok().and_then(aoc::session_age_warning) {
+        eprintln!("{}", warning);
+    }
+}
+
+fn read_session_or_exit() -> String {
+    match aoc::read_session() {
+        Ok(cookie) => cookie,
+        Err(e) => {
+            eprintln!("{}. Set it with `cargo session set`.", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// The settings page redirects to the login page unless the cookie belongs to a logged-in user.
+fn validate(cookie: &str) -> Result<bool, String> {
+    let url = format!("{}/settings", aoc::server());
+    let output = aoc::curl_with_session(
+        cookie,
+        &[
+            "--silent",
+            "--output",
+            "/dev/null",
+            "--write-out",
+            "%{http_code}",
+            &url,
+        ],
+    )
+    .map_err(|e| format!("failed to spawn curl: {}", e))?;
+    let status = String::from_utf8_lossy(&output.stdout);
+    match status.trim() {
+        "200" => Ok(true),
+        "000" => Err(format!("could not reach {}", url)),
+        _ => Ok(false),
+    }
+}
+
+fn read_cookie_from_stdin() -> io::Result<String> {
+    if io::stdin().is_terminal() {
+        print!("Paste your session cookie: ");
+        io::stdout().flush()?;
+    }
+    let mut cookie = String::new();
+    io::stdin().lock().read_line(&mut cookie)?;
+    Ok(cookie)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Failed to process arguments: {}", e);
+            eprintln!("usage: `cargo session set [<cookie>]`, `cargo session show` or `cargo session validate`");
+            process::exit(1);
+        }
+    };
+
+    match args {
+        Args::Set(cookie) => {
+            let cookie = match cookie.map_or_else(read_cookie_from_stdin, Ok) {
+                Ok(cookie) => cookie,
+                Err(e) => {
+                    eprintln!("Failed to read session cookie: {}", e);
+                    process::exit(1);
+                }
+            };
+            let cookie = cookie.trim().trim_start_matches("session=");
+            if cookie.is_empty() {
+                eprintln!("No session cookie given.");
+                process::exit(1);
+            }
+            if !cookie.chars().all(|c| c.is_ascii_hexdigit()) {
+                eprintln!(
+                    "⚠️  The cookie does not look like a session cookie (expected hex digits)."
+                );
+            }
+            match aoc::write_session(cookie) {
+                Ok(path) => {
+                    println!("🎄 Saved session cookie to {:?}.", path);
+                    println!("Type `cargo session validate` to check it.");
+                }
+                Err(e) => {
+                    eprintln!("Failed to save session cookie: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        Args::Show => {
+            let cookie = read_session_or_exit();
+            println!("{}", aoc::mask_session(&cookie));
+            if let Ok(age) = aoc::session_age() {
+                println!("set {} days ago", age.as_secs() / 86400);
+            }
+            print_age_warning();
+        }
+        Args::Validate => {
+            let cookie = read_session_or_exit();
+            match validate(&cookie) {
+                Ok(true) => {
+                    println!("🎄 Session cookie is valid for {}.", aoc::server());
+                    print_age_warning();
+                }
+                Ok(false) => {
+                    eprintln!(
+                        "⚠️  Session cookie was rejected by {}. Refresh it with `cargo session set`.",
+                        aoc::server()
+                    );
+                    process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Failed to validate session cookie: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! `cargo watch-day`: reruns a day's example tests and solution whenever its files change.
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::mpsc,
+    time::Duration,
+};
+
+use advent_of_code::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+fn parse_args() -> Result<u8, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    args.free_from_str()
+}
+
+/// Files that trigger a rerun. Parent directories are watched instead of the files themselves
+/// so that editors which save by renaming, and input files that do not exist yet, are picked up.
+fn watched_files(cwd: &Path, day_padded: &str) -> Vec<PathBuf> {
+    vec![
+        cwd.join("src/bin").join(format!("{}.rs", day_padded)),
+        cwd.join("src/helpers.rs"),
+        cwd.join("src/examples").join(format!("{}.txt", day_padded)),
+        cwd.join("src/inputs").join(format!("{}.txt", day_padded)),
+    ]
+}
+
+fn is_relevant(event: &Event, files: &[PathBuf], helpers_dir: &Path) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event
+            .paths
+            .iter()
+            .any(|p| files.contains(p) || p.starts_with(helpers_dir))
+}
+
+fn run_tests(day_padded: &str) -> bool {
+    let output = match Command::new("cargo")
+        .args(["test", "--bin", day_padded])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("failed to spawn cargo test: {}", e);
+            return false;
+        }
+    };
+
+    if output.status.success() {
+        println!("✅ example tests passed");
+        return true;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.find("failures:") {
+        Some(i) => println!("❌ example tests failed\n{}", stdout[i..].trim_end()),
+        // failed to compile: the interesting part is on stderr.
+        None => println!(
+            "❌ example tests failed\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ),
+    }
+    false
+}
+
+fn run_solution(day_padded: &str) -> Option<Vec<String>> {
+    match Command::new("cargo")
+        .args(["run", "--quiet", "--bin", day_padded])
+        .output()
+    {
+        Ok(output) if output.status.success() => Some(advent_of_code::parse_answers(
+            &String::from_utf8_lossy(&output.stdout),
+        )),
+        Ok(output) => {
+            println!(
+                "❌ solution failed\n{}",
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            );
+            None
+        }
+        Err(e) => {
+            eprintln!("failed to spawn cargo run: {}", e);
+            None
+        }
+    }
+}
+
+fn print_answers(answers: &[String], previous: &[String]) {
+    for (i, answer) in answers.iter().enumerate() {
+        let status = match previous.get(i) {
+            None => "new".to_owned(),
+            Some(p) if p == answer => "unchanged".to_owned(),
+            Some(p) => format!("was {}", p),
+        };
+        println!(
+            "{}Part {}:{} {} {}({}){}",
+            ANSI_BOLD,
+            i + 1,
+            ANSI_RESET,
+            answer,
+            ANSI_ITALIC,
+            status,
+            ANSI_RESET
+        );
+    }
+}
+
+fn run(day_padded: &str, previous: &mut Vec<String>) {
+    println!("----------");
+    println!("{}| Day {} |{}", ANSI_BOLD, day_padded, ANSI_RESET);
+    println!("----------");
+    run_tests(day_padded);
+    if let Some(answers) = run_solution(day_padded) {
+        print_answers(&answers, previous);
+        *previous = answers;
+    }
+}
+
+fn main() {
+    let day = match parse_args() {
+        Ok(day) => day,
+        Err(_) => {
+            eprintln!("Need to specify a day (as integer). example: `cargo watch-day 7`");
+            process::exit(1);
+        }
+    };
+
+    let day_padded = format!("{:02}", day);
+    let cwd = env::current_dir().unwrap();
+    let files = watched_files(&cwd, &day_padded);
+    let helpers_dir = cwd.join("src/helpers");
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            eprintln!("Failed to create file watcher: {}", e);
+            process::exit(1);
+        }
+    };
+
+    for dir in ["src", "src/bin", "src/examples", "src/inputs"] {
+        if let Err(e) = watcher.watch(&cwd.join(dir), RecursiveMode::NonRecursive) {
+            eprintln!("Failed to watch \"{}\": {}", dir, e);
+            process::exit(1);
+        }
+    }
+    if helpers_dir.is_dir() {
+        if let Err(e) = watcher.watch(&helpers_dir, RecursiveMode::Recursive) {
+            eprintln!("Failed to watch \"src/helpers\": {}", e);
+            process::exit(1);
+        }
+    }
+
+    let mut previous = vec![];
+    run(&day_padded, &mut previous);
+    println!("🎄 Watching for changes. Press Ctrl-C to stop.");
+
+    for event in rx.iter() {
+        match event {
+            Ok(event) if is_relevant(&event, &files, &helpers_dir) => {
+                // editors usually emit several events per save.
+                while rx.recv_timeout(DEBOUNCE).is_ok() {}
+                run(&day_padded, &mut previous);
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("watch error: {}", e),
+        }
+    }
+}
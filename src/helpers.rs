@@ -2,3 +2,13 @@
  * Use this file if you want to extract helpers from your solutions.
  * Example import from this file: `use advent_of_code::helpers::example_fn;`.
  */
+
+pub mod animation;
+pub mod cycle;
+pub mod error;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod point;
+pub mod search;
@@ -0,0 +1,306 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use super::{grid::Grid, point::Point};
+
+/// Character used for cells that a frame does not mention.
+pub const BACKGROUND: char = '.';
+
+/// One snapshot of a simulation.
+#[derive(Debug, Clone)]
+pub enum Frame {
+    /// A full grid, drawn with its top left cell at `(0, 0)`.
+    Grid(Grid<char>),
+    /// Only the listed points; everything else is [`BACKGROUND`]. Later points are drawn on top.
+    Points(Vec<(Point<isize>, char)>),
+    /// The listed points drawn on top of the previous frame, so that a long simulation only has
+    /// to record the cells that changed.
+    Changes(Vec<(Point<isize>, char)>),
+}
+
+impl Frame {
+    fn cells(&self) -> Box<dyn Iterator<Item = (Point<isize>, char)> + '_> {
+        match self {
+            Frame::Grid(grid) => Box::new(
+                grid.iter()
+                    .map(|((x, y), c)| (Point::new(x as isize, y as isize), *c)),
+            ),
+            Frame::Points(points) | Frame::Changes(points) => Box::new(points.iter().copied()),
+        }
+    }
+}
+
+/// Frames recorded from a simulation, drawn onto a viewport that fits all of them.
+///
+/// Frames can be played back in the terminal or written out as PPM images or an animated GIF;
+/// every character is one cell, drawn as a `scale`×`scale` square of its colour in images.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<Frame>,
+    colours: HashMap<char, [u8; 3]>,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn push_grid<T>(&mut self, grid: &Grid<T>, f: impl FnMut(&T) -> char) {
+        self.push(Frame::Grid(grid.map(f)));
+    }
+
+    pub fn push_points(&mut self, points: impl IntoIterator<Item = (Point<isize>, char)>) {
+        self.push(Frame::Points(points.into_iter().collect()));
+    }
+
+    pub fn push_changes(&mut self, changes: impl IntoIterator<Item = (Point<isize>, char)>) {
+        self.push(Frame::Changes(changes.into_iter().collect()));
+    }
+
+    /// Uses `rgb` for `c` in images instead of the default colour.
+    pub fn with_colour(mut self, c: char, rgb: [u8; 3]) -> Self {
+        self.colours.insert(c, rgb);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Top left and bottom right corners (inclusive) of the area covered by any frame.
+    pub fn bounds(&self) -> Option<(Point<isize>, Point<isize>)> {
+        self.frames
+            .iter()
+            .flat_map(Frame::cells)
+            .fold(None, |bounds, (p, _)| match bounds {
+                None => Some((p, p)),
+                Some((min, max)) => Some((
+                    Point::new(p.x.min(min.x), p.y.min(min.y)),
+                    Point::new(p.x.max(max.x), p.y.max(max.y)),
+                )),
+            })
+    }
+
+    /// Every frame drawn onto the shared viewport, one at a time.
+    pub fn rasterize(&self) -> impl Iterator<Item = Grid<char>> + '_ {
+        self.bounds().into_iter().flat_map(move |(min, max)| {
+            let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+            let blank = Grid::filled(width, height, BACKGROUND);
+            let mut canvas = blank.clone();
+            self.frames.iter().map(move |frame| {
+                if !matches!(frame, Frame::Changes(_)) {
+                    canvas = blank.clone();
+                }
+                for (p, c) in frame.cells() {
+                    canvas[((p.x - min.x) as usize, (p.y - min.y) as usize)] = c;
+                }
+                canvas.clone()
+            })
+        })
+    }
+
+    /// Redraws every frame in place at `fps` frames per second.
+    pub fn play(&self, fps: f64, out: &mut impl Write) -> io::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / fps.max(0.1));
+        // clear the screen once, then only move the cursor home so frames don't flicker.
+        write!(out, "\x1b[2J")?;
+        for (i, frame) in self.rasterize().enumerate() {
+            write!(
+                out,
+                "\x1b[H{}\n\nframe {}/{}\x1b[K\n",
+                frame,
+                i + 1,
+                self.len()
+            )?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    pub fn colour(&self, c: char) -> [u8; 3] {
+        if let Some(rgb) = self.colours.get(&c) {
+            return *rgb;
+        }
+        match c {
+            BACKGROUND | ' ' => [15, 15, 35],
+            '#' => [230, 230, 230],
+            _ => {
+                // a bright colour that stays the same for the same character.
+                let h = (c as u32).wrapping_mul(2_654_435_761);
+                [
+                    128 + (h >> 24) as u8 / 2,
+                    128 + (h >> 16) as u8 / 2,
+                    128 + (h >> 8) as u8 / 2,
+                ]
+            }
+        }
+    }
+
+    /// Writes `frame_0000.ppm`, `frame_0001.ppm`, ... into `dir`.
+    pub fn write_ppm(&self, dir: &Path, scale: usize) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let scale = scale.max(1);
+        self.rasterize()
+            .enumerate()
+            .map(|(i, frame)| {
+                let path = dir.join(format!("frame_{:04}.ppm", i));
+                let mut out = BufWriter::new(File::create(&path)?);
+                write!(
+                    out,
+                    "P6\n{} {}\n255\n",
+                    frame.width() * scale,
+                    frame.height() * scale
+                )?;
+                for row in frame.rows() {
+                    let line: Vec<u8> = row
+                        .iter()
+                        .flat_map(|c| self.colour(*c).repeat(scale))
+                        .collect();
+                    for _ in 0..scale {
+                        out.write_all(&line)?;
+                    }
+                }
+                out.flush()?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Writes a looping GIF to `path`, showing `fps` frames per second.
+    pub fn write_gif(&self, path: &Path, scale: usize, fps: f64) -> eyre::Result<()> {
+        let Some((min, max)) = self.bounds() else {
+            eyre::bail!("Nothing to animate");
+        };
+        let scale = scale.max(1);
+        let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+        let (width, height) = (
+            u16::try_from(width * scale)?,
+            u16::try_from(height * scale)?,
+        );
+
+        // frames without a full grid may leave background cells anywhere.
+        let palette: BTreeSet<char> = self
+            .frames
+            .iter()
+            .flat_map(Frame::cells)
+            .map(|(_, c)| c)
+            .chain([BACKGROUND])
+            .collect();
+        if palette.len() > 256 {
+            eyre::bail!("A GIF can only show 256 colours, got {}", palette.len());
+        }
+        let index: HashMap<char, u8> = palette
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, i as u8))
+            .collect();
+        let rgb: Vec<u8> = palette.iter().flat_map(|c| self.colour(*c)).collect();
+
+        let mut encoder =
+            gif::Encoder::new(BufWriter::new(File::create(path)?), width, height, &rgb)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        // GIF delays are in hundredths of a second.
+        let delay = (100.0 / fps.max(0.1)).round() as u16;
+        for frame in self.rasterize() {
+            let mut buffer = Vec::with_capacity(width as usize * height as usize);
+            for row in frame.rows() {
+                let line: Vec<u8> = row
+                    .iter()
+                    .flat_map(|c| std::iter::repeat_n(index[c], scale))
+                    .collect();
+                for _ in 0..scale {
+                    buffer.extend_from_slice(&line);
+                }
+            }
+            let mut gif_frame = gif::Frame::from_indexed_pixels(width, height, &buffer, None);
+            gif_frame.delay = delay;
+            encoder.write_frame(&gif_frame)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation() -> Animation {
+        let mut animation = Animation::new();
+        animation.push_points([(Point::new(-1, 0), 'H')]);
+        animation.push_points([(Point::new(1, 1), 'H'), (Point::new(0, 0), 'T')]);
+        animation
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let animation = animation();
+        assert_eq!(
+            animation.bounds(),
+            Some((Point::new(-1, 0), Point::new(1, 1)))
+        );
+        let frames: Vec<String> = animation.rasterize().map(|f| f.to_string()).collect();
+        assert_eq!(frames, vec!["H..\n...", ".T.\n..H"]);
+    }
+
+    #[test]
+    fn test_grid_frames() {
+        let mut animation = Animation::new();
+        let grid = Grid::parse("#.\n.#", Ok).unwrap();
+        animation.push_grid(&grid, |c| *c);
+        animation.push_points([(Point::new(2, 0), 'x')]);
+        let frames: Vec<String> = animation.rasterize().map(|f| f.to_string()).collect();
+        assert_eq!(frames, vec!["#..\n.#.", "..x\n..."]);
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut animation = animation();
+        animation.push_changes([(Point::new(1, 1), '#'), (Point::new(-1, 1), 'H')]);
+        animation.push_changes([]);
+        let frames: Vec<String> = animation.rasterize().map(|f| f.to_string()).collect();
+        assert_eq!(frames, vec!["H..\n...", ".T.\n..H", ".T.\nH.#", ".T.\nH.#"]);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut out = vec![];
+        animation().play(1000.0, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1b[2J\x1b[HH..\n..."));
+        assert!(out.contains("frame 2/2"));
+    }
+
+    #[test]
+    fn test_export() {
+        let dir = std::env::temp_dir().join(format!("aoc-animation-{}", std::process::id()));
+        let animation = animation().with_colour('H', [255, 0, 0]);
+
+        let paths = animation.write_ppm(&dir, 2).unwrap();
+        assert_eq!(paths.len(), 2);
+        let ppm = fs::read(&paths[0]).unwrap();
+        assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+        assert_eq!(ppm.len(), "P6\n6 4\n255\n".len() + 6 * 4 * 3);
+        assert_eq!(&ppm[ppm.len() - 6 * 4 * 3..][..3], &[255, 0, 0]);
+
+        let gif = dir.join("animation.gif");
+        animation.write_gif(&gif, 2, 10.0).unwrap();
+        assert!(fs::read(&gif).unwrap().starts_with(b"GIF89a"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
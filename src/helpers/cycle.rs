@@ -0,0 +1,106 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A run of a deterministic simulation that has started repeating itself.
+///
+/// States `start..start + length` repeat forever, so the state after any number of steps can be
+/// looked up without simulating them.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub length: usize,
+    /// Every state seen, from the initial one up to and including the first repeated one, which is
+    /// at index `start + length`.
+    pub history: Vec<S>,
+}
+
+/// Runs `step` from `initial` until a state's `key` has been seen before.
+///
+/// `key` picks the part of the state that determines the future, leaving out anything that only
+/// accumulates (like a score). The state space must be finite, otherwise this never returns.
+pub fn find_cycle<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle<S>
+where
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut state = initial;
+    loop {
+        let index = history.len();
+        let repeated = seen.insert(key(&state), index);
+        history.push(state);
+        if let Some(start) = repeated {
+            return Cycle {
+                start,
+                length: index - start,
+                history,
+            };
+        }
+        state = step(&history[index]);
+    }
+}
+
+impl<S> Cycle<S> {
+    /// Index into `history` of the state that is equivalent to the state after `steps` steps.
+    pub fn index_after(&self, steps: usize) -> usize {
+        if steps < self.start {
+            steps
+        } else {
+            self.start + (steps - self.start) % self.length
+        }
+    }
+
+    pub fn state_after(&self, steps: usize) -> &S {
+        &self.history[self.index_after(steps)]
+    }
+
+    /// How many whole cycles are skipped to get from `index_after(steps)` to `steps`.
+    pub fn cycles_skipped(&self, steps: usize) -> usize {
+        steps.saturating_sub(self.start) / self.length
+    }
+
+    /// The value of a quantity that changes by the same amount every cycle, such as a height or a
+    /// count, after `steps` steps.
+    pub fn extrapolate(&self, steps: usize, mut value: impl FnMut(&S) -> i64) -> i64 {
+        let per_cycle =
+            value(&self.history[self.start + self.length]) - value(&self.history[self.start]);
+        value(self.state_after(steps)) + self.cycles_skipped(steps) as i64 * per_cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        // 1, 2, 4, 8, 16 % 12 = 4, ...
+        let cycle = find_cycle(1u32, |x| x * 2 % 12, |x| *x);
+        assert_eq!((cycle.start, cycle.length), (2, 2));
+        assert_eq!(cycle.history, vec![1, 2, 4, 8, 4]);
+        assert_eq!(*cycle.state_after(1), 2);
+        assert_eq!(*cycle.state_after(5), 8);
+        assert_eq!(*cycle.state_after(1_000_000_000), 4);
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let cycle = find_cycle(5u32, |x| x.saturating_sub(2), |x| *x);
+        assert_eq!((cycle.start, cycle.length), (3, 1));
+        assert_eq!(*cycle.state_after(100), 0);
+    }
+
+    #[test]
+    fn test_state_key() {
+        // (position on a ring of 3, total distance travelled)
+        let cycle = find_cycle((0, 0), |(p, d)| ((p + 1) % 3, d + 1), |(p, _)| *p);
+        assert_eq!((cycle.start, cycle.length), (0, 3));
+        assert_eq!(cycle.cycles_skipped(10), 3);
+        assert_eq!(*cycle.state_after(10), (1, 1));
+        assert_eq!(cycle.extrapolate(10, |(_, d)| *d), 10);
+        assert_eq!(cycle.extrapolate(2, |(_, d)| *d), 2);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+
+use eyre::WrapErr;
+
+/// Adds the input line an error came from, so reports read like
+/// `line 3: "A Q"` caused by `Invalid opponent play "Q"`.
+pub trait WithLine<T, E> {
+    /// `number` is 1-based.
+    fn with_line(self, number: usize, line: &str) -> eyre::Result<T>;
+}
+
+impl<T, E, R: WrapErr<T, E>> WithLine<T, E> for R {
+    fn with_line(self, number: usize, line: &str) -> eyre::Result<T> {
+        self.wrap_err_with(|| format!("line {}: {:?}", number, line))
+    }
+}
+
+/// Parses every non-blank line of `input` with `f`, stopping at the first line that fails.
+pub fn parse_lines<T>(
+    input: &str,
+    mut f: impl FnMut(&str) -> eyre::Result<T>,
+) -> eyre::Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| f(line).with_line(i + 1, line))
+        .collect()
+}
+
+/// An error for a value that is not one of the `expected` ones.
+pub fn invalid<T: Debug>(what: &str, value: T, expected: &str) -> eyre::Report {
+    eyre::eyre!("Invalid {} {:?}, expected {}", what, value, expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let numbers = parse_lines("1\n\n3\n", |s| Ok(s.parse::<u32>()?)).unwrap();
+        assert_eq!(numbers, vec![1, 3]);
+
+        let e = parse_lines("1\n\nx\n", |s| Ok(s.parse::<u32>()?)).unwrap_err();
+        assert_eq!(e.to_string(), "line 3: \"x\"");
+        assert_eq!(e.root_cause().to_string(), "invalid digit found in string");
+    }
+
+    #[test]
+    fn test_with_line() {
+        let e = Err::<(), _>(invalid("play", "Q", "A, B or C"))
+            .with_line(2, "Q X")
+            .unwrap_err();
+        assert_eq!(
+            format!("{:#}", e),
+            "line 2: \"Q X\": Invalid play \"Q\", expected A, B or C"
+        );
+    }
+}
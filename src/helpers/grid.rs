@@ -0,0 +1,338 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use super::point::Point;
+
+/// Steps to the four orthogonal neighbours: up, right, down, left. `y` grows downwards.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Steps to all eight neighbours, clockwise starting with up.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangular grid stored row by row, addressed by `(x, y)` with `(0, 0)` at the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> eyre::Result<Self> {
+        if width == 0 {
+            eyre::bail!("Invalid grid: rows must not be empty");
+        }
+        if cells.len() != width * height {
+            eyre::bail!(
+                "Invalid grid: {} cells do not fit {}x{}",
+                cells.len(),
+                width,
+                height
+            );
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Panics if `width` is 0.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(width > 0, "A grid needs a width of at least 1");
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a character map, one row per line, converting every character with `f`.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> eyre::Result<T>) -> eyre::Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+        for (i, line) in s.lines().enumerate() {
+            let before = cells.len();
+            for c in line.chars() {
+                let cell =
+                    f(c).map_err(|e| e.wrap_err(format!("Invalid grid at line {}", i + 1)))?;
+                cells.push(cell);
+            }
+            let line_width = cells.len() - before;
+            if line_width == 0 {
+                eyre::bail!("Invalid grid: line {} is empty", i + 1);
+            }
+            match width {
+                None => width = Some(line_width),
+                Some(w) if w != line_width => eyre::bail!(
+                    "Invalid grid: line {} has width {}, expected {}",
+                    i + 1,
+                    line_width,
+                    w
+                ),
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        let width = width.ok_or(eyre::eyre!("Invalid grid with no lines"))?;
+        Self::new(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(x + y * self.width)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(x + y * self.width)
+        } else {
+            None
+        }
+    }
+
+    /// The position one `step` away from `(x, y)`, if it is inside the grid.
+    /// `step` is an offset like `(0, -1)` or a [`Direction`](super::point::Direction).
+    pub fn step(
+        &self,
+        (x, y): (usize, usize),
+        step: impl Into<(isize, isize)>,
+    ) -> Option<(usize, usize)> {
+        let (dx, dy) = step.into();
+        let (u, v) = (x as isize + dx, y as isize + dy);
+        if self.in_bounds(u, v) {
+            Some((u as usize, v as usize))
+        } else {
+            None
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, t)| predicate(t)).map(|(xy, _)| xy)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        xy: (usize, usize),
+        steps: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> {
+        steps
+            .iter()
+            .filter_map(move |step| self.step(xy, *step))
+            .map(move |(u, v)| ((u, v), &self[(u, v)]))
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours((x, y), &NEIGHBOURS_4)
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours((x, y), &NEIGHBOURS_8)
+    }
+
+    /// Cells from `(x, y)` (exclusive) to the edge of the grid, taking `step` each time.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        step: impl Into<(isize, isize)>,
+    ) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let step = step.into();
+        std::iter::successors(self.step((x, y), step), move |xy| self.step(*xy, step))
+            .map(move |xy| (xy, &self[xy]))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Renders the grid with one character per cell.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of the grid", x, y))
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point<usize>) -> &Self::Output {
+        &self[(p.x, p.y)]
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, p: Point<usize>) -> &mut Self::Output {
+        &mut self[(p.x, p.y)]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::point::Direction;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| {
+            c.to_digit(10).ok_or(eyre::eyre!("Invalid digit {}", c))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid[Point::new(1, 1)], 5);
+        assert!(Grid::parse("12\n3\n", Ok).is_err());
+        assert!(Grid::parse("", Ok).is_err());
+        let e = Grid::parse("\n", Ok).unwrap_err();
+        assert_eq!(e.to_string(), "Invalid grid: line 1 is empty");
+        assert!(Grid::<u8>::new(0, 2, vec![]).is_err());
+        assert!(Grid::parse("1a", |c| c.to_digit(10).ok_or(eyre::eyre!("nope"))).is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = digits();
+        assert_eq!(grid.get(0, 0), Some(&1));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert!(grid.in_bounds(2, 1));
+        assert!(!grid.in_bounds(-1, 0));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&[1, 2, 3], &[4, 5, 6]]
+        );
+        let columns: Vec<Vec<u32>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits();
+        let n4: Vec<u32> = grid.neighbours4(0, 0).map(|(_, v)| *v).collect();
+        assert_eq!(n4, vec![2, 4]);
+        let n8: Vec<u32> = grid.neighbours8(1, 0).map(|(_, v)| *v).collect();
+        assert_eq!(n8, vec![3, 6, 5, 4, 1]);
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = digits();
+        let right: Vec<u32> = grid.ray(0, 0, (1, 0)).map(|(_, v)| *v).collect();
+        assert_eq!(right, vec![2, 3]);
+        let up: Vec<(usize, usize)> = grid.ray(2, 1, (0, -1)).map(|(xy, _)| xy).collect();
+        assert_eq!(up, vec![(2, 0)]);
+        assert_eq!(grid.ray(0, 0, (-1, -1)).count(), 0);
+        let down: Vec<u32> = grid.ray(1, 0, Direction::Down).map(|(_, v)| *v).collect();
+        assert_eq!(down, vec![5]);
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = digits();
+        assert_eq!(grid.to_string(), "123\n456");
+        assert_eq!(
+            grid.render(|v| if v % 2 == 0 { '#' } else { '.' }),
+            ".#.\n#.#"
+        );
+    }
+}
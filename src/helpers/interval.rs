@@ -0,0 +1,406 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Sub},
+};
+
+/// Integer-like types that can bound an [`Interval`].
+pub trait Bound: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {$(
+        impl Bound for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+        }
+    )*};
+}
+
+impl_bound!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A half-open interval `start..end`. It is empty when `end <= start`; `new` clamps `end` so
+/// that it never comes before `start`.
+///
+/// Puzzles usually give inclusive ranges like `2-4`; build those with [`Interval::inclusive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    /// `start..end`, excluding `end`.
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// `start..=last`, including `last`.
+    ///
+    /// Panics if `last` is the largest value of `T`, as the end would not fit. Use
+    /// [`Interval::try_inclusive`] for bounds that come from the input.
+    pub fn inclusive(start: T, last: T) -> Self {
+        Self::try_inclusive(start, last)
+            .expect("the last value of an inclusive interval must be below T::MAX")
+    }
+
+    /// `start..=last`, or `None` if `last` is the largest value of `T`.
+    pub fn try_inclusive(start: T, last: T) -> Option<Self> {
+        if last < start {
+            Some(Self::new(start, start))
+        } else {
+            Some(Self::new(start, last.checked_add(T::ONE)?))
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// The first value after the interval.
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// The last value inside the interval, if it is not empty.
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::ONE)
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.start <= *value && *value < self.end
+    }
+
+    /// Whether every value of `other` is also in `self`. An empty interval is inside any other.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether the two intervals share at least one value.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Whether the two intervals overlap or are directly next to each other, so that their union
+    /// is a single interval.
+    pub fn touches(&self, other: &Self) -> bool {
+        !self.is_empty() && !other.is_empty() && self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// The parts of `self` that are not in `other`: up to one piece on either side of it.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+        [
+            Self::new(self.start, other.start),
+            Self::new(other.end, self.end),
+        ]
+        .into_iter()
+        .filter(|piece| !piece.is_empty())
+        .collect()
+    }
+
+    /// The smallest interval containing both.
+    pub fn hull(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            *other
+        } else if other.is_empty() {
+            *self
+        } else {
+            Self::new(self.start.min(other.start), self.end.max(other.end))
+        }
+    }
+}
+
+impl<T: Bound + Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint intervals. Overlapping or adjacent intervals are
+/// merged as they are inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { intervals: vec![] }
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // every interval in `from..to` touches the new one.
+        let from = self
+            .intervals
+            .partition_point(|existing| existing.end < interval.start);
+        let to = self
+            .intervals
+            .partition_point(|existing| existing.start <= interval.end);
+        let merged = self.intervals[from..to]
+            .iter()
+            .fold(interval, |merged, existing| merged.hull(existing));
+        self.intervals.splice(from..to, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|existing| existing.difference(&interval))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in other.iter() {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end <= *value);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn total_len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |total, interval| total + interval.len())
+    }
+
+    /// The smallest interval containing the whole set.
+    pub fn span(&self) -> Option<Interval<T>> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => Some(first.hull(last)),
+            _ => None,
+        }
+    }
+
+    /// The holes between the intervals of the set.
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end, pair[1].start))
+    }
+
+    /// The parts of `bounds` that are not in the set, including any before or after it.
+    pub fn gaps_within(&self, bounds: Interval<T>) -> Vec<Interval<T>> {
+        let mut uncovered = Self::new();
+        uncovered.insert(bounds);
+        for interval in self.iter() {
+            uncovered.remove(*interval);
+        }
+        uncovered.intervals
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn points(interval: &Interval<i32>) -> BTreeSet<i32> {
+        (interval.start()..interval.end()).collect()
+    }
+
+    fn set_points(set: &IntervalSet<i32>) -> BTreeSet<i32> {
+        set.iter().flat_map(points).collect()
+    }
+
+    fn interval() -> impl Strategy<Value = Interval<i32>> {
+        (-20..20, 0..10).prop_map(|(start, len)| Interval::new(start, start + len))
+    }
+
+    fn interval_set() -> impl Strategy<Value = IntervalSet<i32>> {
+        prop::collection::vec(interval(), 0..8).prop_map(IntervalSet::from_iter)
+    }
+
+    #[test]
+    fn test_inclusive() {
+        let interval = Interval::inclusive(2, 4);
+        assert_eq!((interval.start(), interval.end()), (2, 5));
+        assert_eq!(interval.len(), 3);
+        assert_eq!(interval.last(), Some(4));
+        assert!(interval.contains(&4));
+        assert!(!interval.contains(&5));
+        assert!(Interval::inclusive(5, 4).is_empty());
+        assert_eq!(Interval::new(3usize, 1).len(), 0);
+        assert_eq!(Interval::inclusive(0u8, 254).len(), 255);
+        assert_eq!(Interval::try_inclusive(0u8, u8::MAX), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be below T::MAX")]
+    fn test_inclusive_max() {
+        Interval::inclusive(0u8, u8::MAX);
+    }
+
+    #[test]
+    fn test_interval_operations() {
+        let (a, b) = (Interval::inclusive(2, 8), Interval::inclusive(3, 7));
+        assert!(a.contains_interval(&b));
+        assert!(!b.contains_interval(&a));
+        assert_eq!(b.intersection(&a), Some(b));
+        assert_eq!(
+            a.difference(&b),
+            vec![Interval::new(2, 3), Interval::new(8, 9)]
+        );
+        assert!(!Interval::new(0, 2).overlaps(&Interval::new(2, 4)));
+        assert!(Interval::new(0, 2).touches(&Interval::new(2, 4)));
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let set: IntervalSet<i32> = [
+            Interval::inclusive(10, 14),
+            Interval::inclusive(16, 20),
+            Interval::inclusive(12, 18),
+            Interval::inclusive(3, 5),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            vec![Interval::inclusive(3, 5), Interval::inclusive(10, 20)]
+        );
+        assert_eq!(set.total_len(), 14);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![Interval::new(6, 10)]);
+        assert_eq!(
+            set.gaps_within(Interval::inclusive(0, 21)),
+            vec![
+                Interval::new(0, 3),
+                Interval::new(6, 10),
+                Interval::new(21, 22)
+            ]
+        );
+        assert_eq!(set.span(), Some(Interval::inclusive(3, 20)));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_contains(a in interval(), value in -30..30) {
+            prop_assert_eq!(a.contains(&value), points(&a).contains(&value));
+        }
+
+        #[test]
+        fn prop_intersection(a in interval(), b in interval()) {
+            let expected: BTreeSet<i32> = points(&a).intersection(&points(&b)).copied().collect();
+            let actual = a.intersection(&b).map(|i| points(&i)).unwrap_or_default();
+            prop_assert_eq!(&actual, &expected);
+            prop_assert_eq!(a.overlaps(&b), !expected.is_empty());
+            prop_assert_eq!(a.contains_interval(&b), points(&b).is_subset(&points(&a)));
+        }
+
+        #[test]
+        fn prop_difference(a in interval(), b in interval()) {
+            let expected: BTreeSet<i32> = points(&a).difference(&points(&b)).copied().collect();
+            let pieces = a.difference(&b);
+            prop_assert!(pieces.iter().all(|piece| !piece.is_empty() && !piece.overlaps(&b)));
+            let actual: BTreeSet<i32> = pieces.iter().flat_map(points).collect();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn prop_set_is_normalised(set in interval_set()) {
+            for pair in set.iter().collect::<Vec<_>>().windows(2) {
+                prop_assert!(pair[0].end() < pair[1].start());
+            }
+            prop_assert!(set.iter().all(|interval| !interval.is_empty()));
+            prop_assert_eq!(set.total_len() as usize, set_points(&set).len());
+        }
+
+        #[test]
+        fn prop_set_union(a in interval_set(), b in interval_set(), value in -30..30) {
+            let union = a.union(&b);
+            let expected: BTreeSet<i32> = set_points(&a).union(&set_points(&b)).copied().collect();
+            prop_assert_eq!(set_points(&union), expected);
+            prop_assert_eq!(union.contains(&value), a.contains(&value) || b.contains(&value));
+        }
+
+        #[test]
+        fn prop_set_remove(set in interval_set(), removed in interval()) {
+            let mut actual = set.clone();
+            actual.remove(removed);
+            let expected: BTreeSet<i32> = set_points(&set).difference(&points(&removed)).copied().collect();
+            prop_assert_eq!(set_points(&actual), expected);
+        }
+
+        #[test]
+        fn prop_gaps(set in interval_set(), bounds in interval()) {
+            let gaps = set.gaps_within(bounds);
+            let uncovered: BTreeSet<i32> = gaps.iter().flat_map(points).collect();
+            let expected: BTreeSet<i32> = points(&bounds).difference(&set_points(&set)).copied().collect();
+            prop_assert_eq!(uncovered, expected);
+
+            let inner: BTreeSet<i32> = set.gaps().flat_map(|gap| points(&gap)).collect();
+            let span = set.span().map(|span| points(&span)).unwrap_or_default();
+            let expected: BTreeSet<i32> = span.difference(&set_points(&set)).copied().collect();
+            prop_assert_eq!(inner, expected);
+        }
+    }
+}
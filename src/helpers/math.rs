@@ -0,0 +1,163 @@
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all `values` (1 if there are none), or `None` on overflow.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `a * b % m` without overflowing.
+///
+/// Panics if `m` is 0.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    assert!(m > 0, "mul_mod: the modulus must be positive");
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base.pow(exp) % m` by repeated squaring.
+///
+/// Panics if `m` is 0.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "mod_pow: the modulus must be positive");
+    if m == 1 {
+        return 0;
+    }
+    let mut result = 1;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Chinese Remainder Theorem: the smallest `x >= 0` with `x ≡ r (mod m)` for every `(r, m)`,
+/// together with the modulus of the combined solution.
+///
+/// The moduli do not need to be coprime. Fails if the congruences contradict each other or the
+/// combined modulus overflows an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> eyre::Result<(i64, i64)> {
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        if m2 <= 0 {
+            eyre::bail!("Invalid modulus {}", m2);
+        }
+        let r2 = r2.rem_euclid(m2);
+        let (g, p, _) = extended_gcd(m1, m2);
+        if (r2 - r1) % g != 0 {
+            eyre::bail!(
+                "No solution: x ≡ {} (mod {}) contradicts x ≡ {} (mod {})",
+                r1,
+                m1,
+                r2,
+                m2
+            );
+        }
+        let m = (m1 / g).checked_mul(m2).ok_or(eyre::eyre!(
+            "Combined modulus of {} and {} overflows",
+            m1,
+            m2
+        ))?;
+        // x = r1 + m1 * k, where k ≡ (r2 - r1) / g * p (mod m2 / g)
+        let k = ((r2 - r1) / g) as i128 * p as i128 % (m2 / g) as i128;
+        let x = (r1 as i128 + m1 as i128 * k).rem_euclid(m as i128) as i64;
+        Ok((x, m))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm_all([23, 19, 13, 17]), Some(96577));
+        assert_eq!(lcm_all([2, 4, 8]), Some(8));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        let (g, x, y) = extended_gcd(-12, 18);
+        assert_eq!(g, 6);
+        assert_eq!(-12 * x + 18 * y, 6);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2, 0, 7), 1);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+        assert_eq!(mul_mod(u64::MAX, u64::MAX, 10), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be positive")]
+    fn test_mod_pow_zero_modulus() {
+        mod_pow(2, 3, 0);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]).unwrap(), (23, 105));
+        // not coprime, but consistent
+        assert_eq!(crt(&[(2, 4), (4, 6)]).unwrap(), (10, 12));
+        assert!(crt(&[(1, 4), (2, 6)]).is_err());
+        assert!(crt(&[(0, i64::MAX), (1, i64::MAX - 1)]).is_err());
+        assert_eq!(crt(&[]).unwrap(), (0, 1));
+    }
+}
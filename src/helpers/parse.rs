@@ -0,0 +1,192 @@
+use nom::{
+    combinator::cut,
+    error::{ParseError, VerboseError, VerboseErrorKind},
+    IResult,
+};
+
+/// Result of a parser that collects [`context`](nom::error::context) labels along the way.
+pub type VerboseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// A nom error that can say what went wrong and where.
+pub trait ParseErrorContext<'a> {
+    /// Each problem with the input remaining when it happened, innermost first.
+    fn details(&self) -> Vec<(&'a str, String)>;
+}
+
+impl<'a> ParseErrorContext<'a> for nom::error::Error<&'a str> {
+    fn details(&self) -> Vec<(&'a str, String)> {
+        vec![(self.input, format!("{} failed", self.code.description()))]
+    }
+}
+
+impl<'a> ParseErrorContext<'a> for VerboseError<&'a str> {
+    fn details(&self) -> Vec<(&'a str, String)> {
+        self.errors
+            .iter()
+            .map(|(rest, kind)| {
+                let message = match kind {
+                    VerboseErrorKind::Context(label) => format!("while parsing {}", label),
+                    VerboseErrorKind::Char(c) => format!("expected '{}'", c),
+                    VerboseErrorKind::Nom(code) => format!("{} failed", code.description()),
+                };
+                (*rest, message)
+            })
+            .collect()
+    }
+}
+
+/// Runs `parser` over the whole of `input`, which must be consumed except for trailing whitespace.
+///
+/// On failure the report points at the offending line and column:
+///
+/// ```text
+/// Parse error at line 2, column 6: Digit failed
+///   |
+/// 2 | addx x
+///   |      ^
+/// ```
+///
+/// Parsers returning a [`VerboseResult`] also list their context labels.
+pub fn parse_all<'a, O, E: ParseErrorContext<'a>>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O, E>,
+) -> eyre::Result<O> {
+    match parser(input) {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(report(
+            input,
+            &[(rest.trim_start(), "unexpected trailing input".to_owned())],
+        )),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(report(input, &e.details())),
+        Err(nom::Err::Incomplete(_)) => {
+            Err(report(input, &[("", "unexpected end of input".to_owned())]))
+        }
+    }
+}
+
+/// Like nom's `separated_list1`, but once a separator is followed by more than whitespace the
+/// next item must parse. Its error is passed on, instead of ending the list early and leaving
+/// [`parse_all`] to report the whole item as trailing input.
+pub fn separated_items<'a, O, O2, E: ParseError<&'a str>>(
+    mut sep: impl FnMut(&'a str) -> IResult<&'a str, O2, E>,
+    mut item: impl FnMut(&'a str) -> IResult<&'a str, O, E>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E> {
+    move |s| {
+        let (mut s, first) = item(s)?;
+        let mut items = vec![first];
+        while let Ok((rest, _)) = sep(s) {
+            if rest.trim().is_empty() {
+                break;
+            }
+            let (rest, next) = cut(&mut item)(rest)?;
+            items.push(next);
+            s = rest;
+        }
+        Ok((s, items))
+    }
+}
+
+/// 1-based line and column of `rest` within `input`, plus the whole line it is on.
+fn locate<'a>(input: &'a str, rest: &str) -> (usize, usize, &'a str) {
+    let offset = input.len().saturating_sub(rest.len());
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    let text = input[line_start..].lines().next().unwrap_or_default();
+    (line, column, text)
+}
+
+fn report(input: &str, details: &[(&str, String)]) -> eyre::Report {
+    let Some(((rest, message), contexts)) = details.split_first() else {
+        return eyre::eyre!("Parse error");
+    };
+    let (line, column, text) = locate(input, rest);
+    let gutter = " ".repeat(line.to_string().len());
+    let mut out = format!(
+        "Parse error at line {}, column {}: {}\n{} |\n{} | {}\n{} | {}^",
+        line,
+        column,
+        message,
+        gutter,
+        line,
+        text,
+        gutter,
+        " ".repeat(column - 1)
+    );
+    for (rest, message) in contexts {
+        let (line, column, _) = locate(input, rest);
+        out += &format!("\n{} (line {}, column {})", message, line, column);
+    }
+    eyre::eyre!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        bytes::complete::tag,
+        character::complete::{char, digit1, line_ending},
+        error::context,
+        multi::separated_list1,
+        sequence::{delimited, preceded},
+    };
+
+    use super::*;
+
+    fn numbers(s: &str) -> IResult<&str, Vec<&str>> {
+        separated_list1(line_ending, preceded(tag("n "), digit1))(s)
+    }
+
+    fn bracketed(s: &str) -> VerboseResult<'_, &str> {
+        context(
+            "bracket",
+            delimited(char('['), context("number", digit1), char(']')),
+        )(s)
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(parse_all("n 1\nn 22\n", numbers).unwrap(), vec!["1", "22"]);
+    }
+
+    #[test]
+    fn test_trailing_input() {
+        let e = parse_all("n 1\nn 22\nn x\n", numbers).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Parse error at line 3, column 1: unexpected trailing input\n  |\n3 | n x\n  | ^"
+        );
+    }
+
+    #[test]
+    fn test_separated_items() {
+        let numbers = |s| separated_items(line_ending, preceded(tag("n "), digit1))(s);
+        let parsed: IResult<&str, Vec<&str>> = numbers("n 1\nn 22\n");
+        assert_eq!(parsed.unwrap(), ("\n", vec!["1", "22"]));
+
+        let e = parse_all("n 1\nn 22\nn x\n", numbers).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "Parse error at line 3, column 3: Digit failed\n  |\n3 | n x\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_error_location() {
+        let second: fn(&str) -> IResult<&str, &str> = |s| preceded(tag("n 1\n"), tag("n 3"))(s);
+        let e = parse_all("n 1\nn 2", second).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("Parse error at line 2, column 1: Tag failed\n  |\n2 | n 2\n  | ^"));
+    }
+
+    #[test]
+    fn test_verbose_context() {
+        let e = parse_all("[12", bracketed).unwrap_err().to_string();
+        assert!(e.starts_with("Parse error at line 1, column 4: expected ']'"));
+        assert!(e.ends_with("while parsing bracket (line 1, column 1)"));
+
+        let e = parse_all("[x]", bracketed).unwrap_err().to_string();
+        assert!(e.contains("while parsing number (line 1, column 2)"));
+    }
+}
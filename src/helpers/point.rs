@@ -0,0 +1,302 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A 2D point or offset. As in [`Grid`](super::grid::Grid), `y` grows downwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+fn abs_diff<T: Ord + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Point<T> {
+    pub fn manhattan(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Number of king moves between two points.
+    pub fn chebyshev(&self, other: &Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+macro_rules! impl_signed_point {
+    ($($t:ty),*) => {$(
+        impl Point<$t> {
+            pub fn signum(&self) -> Self {
+                Point::new(self.x.signum(), self.y.signum())
+            }
+
+            /// One king move towards `target`, or no move if already there.
+            pub fn step_towards(&self, target: &Self) -> Self {
+                *self + (*target - *self).signum()
+            }
+
+            /// Rotates by 90° clockwise (on screen) around the origin.
+            pub fn rotate_right(&self) -> Self {
+                Point::new(-self.y, self.x)
+            }
+
+            /// Rotates by 90° counter-clockwise (on screen) around the origin.
+            pub fn rotate_left(&self) -> Self {
+                Point::new(self.y, -self.x)
+            }
+
+            pub fn neighbours4(&self) -> impl Iterator<Item = Self> {
+                let p = *self;
+                Direction::CARDINAL.into_iter().map(move |d| p + d.into())
+            }
+
+            pub fn neighbours8(&self) -> impl Iterator<Item = Self> {
+                let p = *self;
+                Direction::ALL.into_iter().map(move |d| p + d.into())
+            }
+        }
+
+        impl From<Direction> for Point<$t> {
+            fn from(d: Direction) -> Self {
+                let (dx, dy) = d.offset();
+                Point::new(dx as $t, dy as $t)
+            }
+        }
+    )*};
+}
+
+impl_signed_point!(i32, i64, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The 4-way directions, clockwise starting with up.
+    pub const CARDINAL: [Direction; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The 8-way directions, clockwise starting with up.
+    pub const ALL: [Direction; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap_or_default()
+    }
+
+    fn rotate(&self, eighths: usize) -> Self {
+        Self::ALL[(self.index() + eighths) % 8]
+    }
+
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !Self::CARDINAL.contains(self)
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(&self) -> Self {
+        self.rotate(6)
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+}
+
+impl From<Direction> for (isize, isize) {
+    fn from(d: Direction) -> Self {
+        d.offset()
+    }
+}
+
+/// Parses `U/D/L/R`, `^v<>` and compass `N/S/E/W` notations, plus compass diagonals like `NE`.
+impl FromStr for Direction {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" | "^" | "N" => Ok(Self::Up),
+            "D" | "v" | "S" => Ok(Self::Down),
+            "L" | "<" | "W" => Ok(Self::Left),
+            "R" | ">" | "E" => Ok(Self::Right),
+            "NE" => Ok(Self::UpRight),
+            "SE" => Ok(Self::DownRight),
+            "SW" => Ok(Self::DownLeft),
+            "NW" => Ok(Self::UpLeft),
+            _ => Err(eyre::eyre!("Invalid direction: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = eyre::Report;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Self::from_str(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point::new(1, 2) + Point::new(3, -4);
+        assert_eq!(p, Point::new(4, -2));
+        assert_eq!(p - Point::new(4, 4), Point::new(0, -6));
+        assert_eq!(-p * 2, Point::new(-8, 4));
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(1usize, 5), Point::new(4usize, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+    }
+
+    #[test]
+    fn test_step_towards() {
+        let p = Point::new(0isize, 0);
+        assert_eq!(p.step_towards(&Point::new(2, -5)), Point::new(1, -1));
+        assert_eq!(p.step_towards(&Point::new(0, 3)), Point::new(0, 1));
+        assert_eq!(p.step_towards(&p), p);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let up: Point<i32> = Direction::Up.into();
+        assert_eq!(up.rotate_right(), Direction::Right.into());
+        assert_eq!(up.rotate_left(), Direction::Left.into());
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpRight.turn_left(), Direction::UpLeft);
+        assert_eq!(Direction::DownLeft.opposite(), Direction::UpRight);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(0i64, 0);
+        assert_eq!(p.neighbours4().count(), 4);
+        assert_eq!(p.neighbours8().filter(|n| n.chebyshev(&p) == 1).count(), 8);
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        for s in ["U", "^", "N"] {
+            assert_eq!(Direction::from_str(s).unwrap(), Direction::Up);
+        }
+        assert_eq!(Direction::try_from('v').unwrap(), Direction::Down);
+        assert_eq!(Direction::from_str("W").unwrap(), Direction::Left);
+        assert_eq!(Direction::from_str(">").unwrap(), Direction::Right);
+        assert!(Direction::from_str("X").is_err());
+        assert!(Direction::from_str("NE").unwrap().is_diagonal());
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Everything a search has learned: the cost to every node it reached, the node each was reached
+/// from, and the first goal found (if any).
+#[derive(Debug, Clone)]
+pub struct SearchResult<N, C> {
+    pub distances: HashMap<N, C>,
+    pub predecessors: HashMap<N, N>,
+    pub goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> SearchResult<N, C> {
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    /// The nodes from one of the sources up to and including `node`.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        let mut current = node;
+        while let Some(previous) = self.predecessors.get(current) {
+            path.push(previous.clone());
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+}
+
+/// Breadth-first search where every edge costs one step.
+///
+/// Stops as soon as a node satisfying `is_goal` is reached; pass `|_| false` to explore the whole
+/// reachable graph.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut result = SearchResult {
+        distances: HashMap::new(),
+        predecessors: HashMap::new(),
+        goal: None,
+    };
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            result.goal = Some(node);
+            break;
+        }
+        let steps = result.distances[&node] + 1;
+        for next in neighbours(&node) {
+            if !result.distances.contains_key(&next) {
+                result.distances.insert(next.clone(), steps);
+                result.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    result
+}
+
+/// Dijkstra's algorithm: `neighbours` yields each neighbour along with the cost to move there.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` estimates the remaining cost to a goal; it must never overestimate it
+/// (and should be consistent) for the goal distance to be the shortest one.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> SearchResult<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut result = SearchResult {
+        distances: HashMap::new(),
+        predecessors: HashMap::new(),
+        goal: None,
+    };
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        let priority = heuristic(&start);
+        result.distances.insert(start.clone(), C::default());
+        queue.push(Visitable {
+            priority,
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(Visitable { cost, node, .. }) = queue.pop() {
+        // a cheaper way to this node has been found after this entry was queued.
+        if result.distances.get(&node).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        if is_goal(&node) {
+            result.goal = Some(node);
+            break;
+        }
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            if result
+                .distances
+                .get(&next)
+                .is_none_or(|best| next_cost < *best)
+            {
+                result.distances.insert(next.clone(), next_cost);
+                result.predecessors.insert(next.clone(), node.clone());
+                queue.push(Visitable {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Queue entry ordered so that `BinaryHeap` pops the lowest priority first.
+struct Visitable<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Visitable<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Visitable<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Visitable<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Visitable<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -1- 1 -1- 2
+    //  \          |
+    //   5         1
+    //    \        |
+    //     4 -1--- 3
+    fn weighted(n: &u32) -> Vec<(u32, u32)> {
+        match n {
+            0 => vec![(1, 1), (4, 5)],
+            1 => vec![(0, 1), (2, 1)],
+            2 => vec![(1, 1), (3, 1)],
+            3 => vec![(2, 1), (4, 1)],
+            4 => vec![(0, 5), (3, 1)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(n: &u32) -> Vec<u32> {
+        weighted(n).into_iter().map(|(m, _)| m).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let result = bfs([0], unweighted, |n| *n == 4);
+        assert_eq!(result.goal_distance(), Some(1));
+        assert_eq!(result.goal_path(), Some(vec![0, 4]));
+    }
+
+    #[test]
+    fn test_bfs_all_reachable() {
+        let result = bfs([0], unweighted, |_| false);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.distances.len(), 5);
+        assert_eq!(result.path_to(&3).map(|p| p.len()), Some(3));
+        assert_eq!(result.path_to(&7), None);
+    }
+
+    #[test]
+    fn test_bfs_multiple_sources() {
+        let result = bfs([0, 3], unweighted, |_| false);
+        assert_eq!(result.distance(&2), Some(1));
+        assert_eq!(result.distance(&4), Some(1));
+        assert_eq!(result.path_to(&2), Some(vec![3, 2]));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra([0], weighted, |n| *n == 4);
+        assert_eq!(result.goal_distance(), Some(4));
+        assert_eq!(result.goal_path(), Some(vec![0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_astar() {
+        // distance along the ring in the "1, 2, 3" direction never overestimates.
+        let heuristic = |n: &u32| 4 - n.min(&4);
+        let result = astar([0], weighted, heuristic, |n| *n == 4);
+        assert_eq!(result.goal_distance(), Some(4));
+        assert_eq!(result.goal_path(), Some(vec![0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let result = dijkstra([0], weighted, |n| *n == 9);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.goal_path(), None);
+    }
+}
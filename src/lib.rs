@@ -6,7 +6,9 @@
 use std::env;
 use std::fs;
 
+pub mod aoc;
 pub mod helpers;
+pub mod log;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -20,7 +22,11 @@ macro_rules! solve {
         use std::fmt::Display;
         use std::time::Instant;
 
-        fn print_result<T: Display>(func: impl FnOnce(&str) -> color_eyre::Result<T>, input: &str) {
+        fn print_result<T: Display>(
+            func: impl FnOnce(&str) -> color_eyre::Result<T>,
+            input: &str,
+            context: String,
+        ) {
             let timer = Instant::now();
             let result = func(input);
             let elapsed = timer.elapsed();
@@ -32,13 +38,15 @@ macro_rules! solve {
                     );
                 }
                 Err(err) => {
-                    println!("not solved. {:?}", err)
+                    println!("not solved. {:?}", err.wrap_err(context))
                 }
             }
         }
 
         println!("🎄 {}Part {}{} 🎄", ANSI_BOLD, $part, ANSI_RESET);
-        print_result($solver, $input);
+        // errors name the day and part they come from, e.g. "Day 02, part 1".
+        let context = format!("Day {}, part {}", env!("CARGO_BIN_NAME"), $part);
+        print_result($solver, $input, context);
     }};
 }
 
@@ -78,6 +86,26 @@ pub fn parse_exec_time(output: &str) -> f64 {
     })
 }
 
+/// Extracts the printed answer of every part from the output of a solution binary.
+pub fn parse_answers(output: &str) -> Vec<String> {
+    let mut answers: Vec<Vec<&str>> = vec![];
+    for line in output.lines() {
+        if line.starts_with("🎄") && line.contains("Part") {
+            answers.push(vec![]);
+        } else if let Some(answer) = answers.last_mut() {
+            let line = match line.find("(elapsed:") {
+                Some(i) => line[..i].trim_end_matches(ANSI_ITALIC),
+                None => line,
+            };
+            answer.push(line.trim_end());
+        }
+    }
+    answers
+        .into_iter()
+        .map(|lines| lines.join("\n").trim().to_owned())
+        .collect()
+}
+
 /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
 #[cfg(test)]
 macro_rules! assert_approx_eq {
@@ -123,4 +151,17 @@ mod tests {
             10400.50_f64
         );
     }
+
+    #[test]
+    fn test_parse_answers() {
+        assert_eq!(
+            parse_answers(&format!(
+                "🎄 {b}Part 1{r} 🎄\n24000 {i}(elapsed: 74.13ns){r}\n🎄 {b}Part 2{r} 🎄\nnot solved. invalid\n",
+                b = ANSI_BOLD,
+                i = ANSI_ITALIC,
+                r = ANSI_RESET
+            )),
+            vec!["24000", "not solved. invalid"]
+        );
+    }
 }
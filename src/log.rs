@@ -0,0 +1,99 @@
+//! Debug output for solutions that stays quiet unless asked for.
+//!
+//! Pass `-v` (debug) or `-vv` (trace) to a solution, e.g. `cargo solve 05 -- -vv`, or set
+//! `AOC_VERBOSE=1` / `AOC_VERBOSE=2`. Messages go to stderr so they never mix with the answers.
+//! Building without the default `log` feature compiles the macros to nothing.
+use std::{env, sync::OnceLock};
+
+pub const DEBUG: u8 = 1;
+pub const TRACE: u8 = 2;
+
+static LEVEL: OnceLock<u8> = OnceLock::new();
+
+fn level_from(args: impl Iterator<Item = String>, env_level: Option<String>) -> u8 {
+    let from_args = args
+        .map(|arg| match arg.as_str() {
+            "-v" | "--verbose" => DEBUG,
+            "-vv" => TRACE,
+            _ => 0,
+        })
+        .max()
+        .unwrap_or(0);
+    let from_env = env_level
+        .and_then(|level| level.trim().parse::<u8>().ok())
+        .unwrap_or(0);
+    from_args.max(from_env)
+}
+
+/// The verbosity requested on the command line or through `AOC_VERBOSE`.
+pub fn level() -> u8 {
+    *LEVEL.get_or_init(|| level_from(env::args().skip(1), env::var("AOC_VERBOSE").ok()))
+}
+
+pub fn enabled(level: u8) -> bool {
+    self::level() >= level
+}
+
+/// Prints to stderr when running with `-v` or more.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::DEBUG) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Prints to stderr when running with `-vv`.
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::TRACE) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+// the arguments are still type checked, but never evaluated.
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_level_from() {
+        assert_eq!(level_from(args(&[]), None), 0);
+        assert_eq!(level_from(args(&["-v"]), None), DEBUG);
+        assert_eq!(level_from(args(&["--other", "-vv"]), None), TRACE);
+        assert_eq!(level_from(args(&[]), Some("2".to_owned())), TRACE);
+        assert_eq!(level_from(args(&["-v"]), Some("nope".to_owned())), DEBUG);
+    }
+}